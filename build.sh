@@ -1,6 +1,11 @@
 #!/bin/bash
 set -e
 
+# the workspace and the sim tests need the requester sample contract, fetch it when the checkout doesn't include it
+if [ ! -f ./requester/Cargo.toml ]; then
+    git submodule update --init requester || git clone https://github.com/fluxprotocol/requester-sample-contract requester
+fi
+
 RUSTFLAGS='-C link-arg=-s' cargo build --target wasm32-unknown-unknown --release
 
 if [ ! -d ./res ]; then
@@ -10,3 +15,4 @@ fi
 cp ./target/wasm32-unknown-unknown/release/oracle.wasm ./res
 cp ./target/wasm32-unknown-unknown/release/request_interface.wasm ./res
 cp ./target/wasm32-unknown-unknown/release/token.wasm ./res
+cp ./target/wasm32-unknown-unknown/release/mock_arbitrator.wasm ./res
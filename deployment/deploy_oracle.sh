@@ -7,15 +7,33 @@ gov=${gov:-flux-dev}
 finalArbitrator=${finalArbitrator:-flux-dev}
 stakeToken=${stakeToken:-v2.wnear.flux-dev}
 paymentToken=${paymentToken:-v2.wnear.flux-dev}
+# json list of {"token", "validity_bond", "fee_multiplier", "fee_divisor"} requesters can pay in next to paymentToken
+paymentTokens=${paymentTokens:-[]}
 validityBond=${validityBond:-1000000000000000000000000}
 maxOutcomes=${maxOutcomes:-8}
 defaultChallengeWindowDuration=${defaultChallengeWindowDuration:-120000000000}
 minInitialChallengeWindowDuration=${minInitialChallengeWindowDuration:-120000000000}
+requestExpiryDuration=${requestExpiryDuration:-86400000000000}
+maxSettlementDuration=${maxSettlementDuration:-2592000000000000}
+forfeitUnrevealedStakes=${forfeitUnrevealedStakes:-false}
+treasury=${treasury:-flux-dev}
+treasuryShare=${treasuryShare:-0}
+burnShare=${burnShare:-0}
+burnAccount=${burnAccount:-system}
+invokeFinalArbitratorContract=${invokeFinalArbitratorContract:-false}
+finalArbitratorDeadline=${finalArbitratorDeadline:-604800000000000}
+finalArbitratorFallback=${finalArbitratorFallback:-LastBondedOutcome}
+unbondedOutcomePolicy=${unbondedOutcomePolicy:-Refund}
+registrationBond=${registrationBond:-null}
+registrationCooldown=${registrationCooldown:-604800000000000}
+registrationStakeMultiplier=${registrationStakeMultiplier:-null}
+governanceDelay=${governanceDelay:-0}
 finalArbitratorInvokeAmount=${finalArbitratorInvokeAmount:-100000000000000000000000000}
 # resolutionFeePercentage=${resolutionFeePercentage:-5000}
 fluxMarketCap=${fluxMarketCap:-10000000000000}
 totalValueStaked=${totalValueStaked:-1000}
 resolutionFeePercentage=${totalValueStaked:-1000}
+minResolutionFee=${minResolutionFee:-100000000000000000000000}
 
 while [ $# -gt 0 ]; do
 
@@ -28,4 +46,4 @@ while [ $# -gt 0 ]; do
   shift
 done
 
-NEAR_ENV=$network near deploy --accountId $accountId --wasmFile ./res/oracle.wasm --initFunction new --initArgs '{ "config": { "gov": "'$gov'", "final_arbitrator": "'$finalArbitrator'", "stake_token": "'$stakeToken'", "payment_token": "'$paymentToken'", "validity_bond": "'$validityBond'", "max_outcomes": '$maxOutcomes', "default_challenge_window_duration": "'$defaultChallengeWindowDuration'", "min_initial_challenge_window_duration": "'$minInitialChallengeWindowDuration'", "final_arbitrator_invoke_amount": "'$finalArbitratorInvokeAmount'", "resolution_fee_percentage": '$resolutionFeePercentage', "fee": {"flux_market_cap": "'$fluxMarketCap'", "total_value_staked":"'$totalValueStaked'", "resolution_fee_percentage": '$resolutionFeePercentage' } } }'
+NEAR_ENV=$network near deploy --accountId $accountId --wasmFile ./res/oracle.wasm --initFunction new --initArgs '{ "config": { "gov": "'$gov'", "final_arbitrator": "'$finalArbitrator'", "stake_token": "'$stakeToken'", "payment_token": "'$paymentToken'", "payment_tokens": '$paymentTokens', "validity_bond": "'$validityBond'", "max_outcomes": '$maxOutcomes', "default_challenge_window_duration": "'$defaultChallengeWindowDuration'", "min_initial_challenge_window_duration": "'$minInitialChallengeWindowDuration'", "request_expiry_duration": "'$requestExpiryDuration'", "max_settlement_duration": "'$maxSettlementDuration'", "forfeit_unrevealed_stakes": '$forfeitUnrevealedStakes', "treasury": "'$treasury'", "treasury_share": '$treasuryShare', "burn_share": '$burnShare', "burn_account": "'$burnAccount'", "invoke_final_arbitrator_contract": '$invokeFinalArbitratorContract', "final_arbitrator_deadline": "'$finalArbitratorDeadline'", "final_arbitrator_fallback": "'$finalArbitratorFallback'", "unbonded_outcome_policy": "'$unbondedOutcomePolicy'", "registration_bond": '$registrationBond', "registration_cooldown": "'$registrationCooldown'", "registration_stake_multiplier": '$registrationStakeMultiplier', "governance_delay": "'$governanceDelay'", "final_arbitrator_invoke_amount": "'$finalArbitratorInvokeAmount'", "resolution_fee_percentage": '$resolutionFeePercentage', "fee": {"flux_market_cap": "'$fluxMarketCap'", "total_value_staked":"'$totalValueStaked'", "resolution_fee_percentage": '$resolutionFeePercentage', "min_resolution_fee": "'$minResolutionFee'" } } }'
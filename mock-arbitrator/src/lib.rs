@@ -0,0 +1,37 @@
+/**
+* Final arbitrator contract used by the oracle's simulation tests
+*
+* It answers `dr_arbitrate` right away with the first contested outcome, which is the outcome bonded in the round
+* before the one that invoked the final arbitrator, so its answer differs from the `LastBondedOutcome` fallback.
+*/
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde_json::Value;
+use near_sdk::{env, near_bindgen};
+
+#[cfg(target_arch = "wasm32")]
+#[global_allocator]
+static ALLOC: near_sdk::wee_alloc::WeeAlloc<'_> = near_sdk::wee_alloc::WeeAlloc::INIT;
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MockArbitrator {}
+
+impl Default for MockArbitrator {
+    fn default() -> Self {
+        env::panic(b"MockArbitrator is not initialized");
+    }
+}
+
+#[near_bindgen]
+impl MockArbitrator {
+    #[init]
+    pub fn new() -> Self {
+        assert!(!env::state_exists(), "Already initialized");
+        Self {}
+    }
+
+    #[allow(unused_variables)]
+    pub fn dr_arbitrate(&self, request: Value, contested_outcomes: Vec<Value>) -> Option<Value> {
+        contested_outcomes.into_iter().next()
+    }
+}
@@ -1,5 +1,6 @@
-use crate::data_request::DataRequestDataType;
+use crate::data_request::{DataRequestDataType, Tolerance, PERCENTAGE_DIVISOR};
 use crate::*;
+use near_sdk::json_types::Base64VecU8;
 use near_sdk::serde::{Deserialize, Serialize};
 use types::*;
 
@@ -17,6 +18,8 @@ pub struct NewDataRequestArgs {
     pub challenge_period: WrappedTimestamp,
     pub data_type: DataRequestDataType,
     pub creator: AccountId,
+    pub reveal_period: Option<WrappedTimestamp>, // enables commit-reveal staking, duration of each round's reveal phase
+    pub settlement_time: Option<WrappedTimestamp>, // time before which the request can't be staked on
 }
 
 impl Contract {
@@ -61,6 +64,42 @@ impl Contract {
             MIN_OUTCOMES,
             config.max_outcomes
         );
+        if let Some(reveal_period) = data_request.reveal_period {
+            let reveal_period: u64 = reveal_period.into();
+            assert!(
+                reveal_period >= min_initial_challenge_window_duration
+                    && reveal_period <= default_challenge_window_duration * MIN_PERIOD_MULTIPLIER,
+                "Reveal period should be between {} and {}",
+                min_initial_challenge_window_duration,
+                default_challenge_window_duration * MIN_PERIOD_MULTIPLIER
+            );
+        }
+        if let Some(settlement_time) = data_request.settlement_time {
+            let max_settlement_time =
+                env::block_timestamp() + u64::from(config.max_settlement_duration);
+            assert!(
+                u64::from(settlement_time) >= env::block_timestamp(),
+                "Settlement time can't be before {}",
+                env::block_timestamp()
+            );
+            assert!(
+                u64::from(settlement_time) <= max_settlement_time,
+                "Settlement time can't be after {}",
+                max_settlement_time
+            );
+        }
+        match data_request.data_type {
+            DataRequestDataType::Range(_, Tolerance::Relative(percentage)) => assert!(
+                percentage <= PERCENTAGE_DIVISOR,
+                "Relative tolerance can't exceed {}",
+                PERCENTAGE_DIVISOR
+            ),
+            DataRequestDataType::Vector(_) => assert!(
+                data_request.sources.len() != 0 && data_request.outcomes.is_none(),
+                "Vector requests need at least one source and can't have outcomes"
+            ),
+            _ => (),
+        }
     }
 }
 
@@ -70,6 +109,12 @@ pub struct StakeDataRequestArgs {
     pub outcome: Outcome,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct CommitDataRequestArgs {
+    pub id: U64,
+    pub commitment: Base64VecU8, // see `resolution_window::commitment_hash` or the `dr_commitment_hash` view
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ChallengeDataRequestArgs {
     pub id: U64,
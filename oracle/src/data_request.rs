@@ -1,10 +1,13 @@
 use crate::*;
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::Vector;
-use near_sdk::json_types::{U128, U64};
+use near_sdk::collections::{LookupSet, Vector};
+use near_sdk::json_types::{Base64VecU8, U128, U64};
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::{env, ext_contract, AccountId, Balance, Promise, PromiseOrValue};
+use near_sdk::serde_json;
+use near_sdk::{
+    env, ext_contract, AccountId, Balance, Gas, Promise, PromiseOrValue, PromiseResult,
+};
 
 use crate::fungible_token::fungible_token_transfer;
 use crate::helpers::multiply_stake;
@@ -14,10 +17,21 @@ use crate::types::*;
 
 pub const PERCENTAGE_DIVISOR: u16 = 10_000;
 pub const FINALIZATION_GAS: u64 = 250_000_000_000_000;
+const GAS_PROCEED_FINALIZATION: Gas = FINALIZATION_GAS / 10;
+// Time after which a delivery that's still pending is assumed to have lost its callback and can be retried
+pub const DELIVERY_RETRY_TIMEOUT: Duration = 3_600_000_000_000;
+// Finalizing on the final arbitrator's answer delivers the outcome, handles the delivery result, sends the burned share of slashed
+// stake to the burn account and returns the validity bond
+const GAS_FINAL_ARBITRATOR_CALLBACK: Gas = FINALIZATION_GAS / 10
+    + requester_handler::GAS_SET_OUTCOME
+    + GAS_PROCEED_FINALIZATION
+    + 2 * fungible_token::GAS_BASE_TRANSFER
+    + treasury::GAS_RESOLVE_TRANSFER;
 
 #[ext_contract]
 trait ExtSelf {
-    fn dr_proceed_finalization(request_id: U64, sender: AccountId);
+    fn dr_proceed_finalization(request_id: U64);
+    fn dr_final_arbitrator_callback(request_id: U64);
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Clone)]
@@ -26,10 +40,78 @@ pub struct Source {
     pub source_path: String, // data.price.usdeth
 }
 
+#[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub enum Tolerance {
+    Absolute(U128), // max difference between two answers' raw values
+    Relative(u16),  // max difference relative to the largest answer, denominated in 1e4 so 100 = 1%
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Debug, PartialEq, Clone)]
 pub enum DataRequestDataType {
     Number(U128),
     String,
+    Range(U128, Tolerance), // numbers within the tolerance of each other are treated as the same outcome
+    Vector(U128),           // one number per source, all using the same multiplier
+}
+
+// @returns the absolute difference between two number answers' raw values
+fn number_difference(a: &AnswerNumberType, b: &AnswerNumberType) -> u128 {
+    let (a_value, b_value) = (u128::from(a.value), u128::from(b.value));
+    if a.negative == b.negative {
+        std::cmp::max(a_value, b_value) - std::cmp::min(a_value, b_value)
+    } else {
+        a_value + b_value
+    }
+}
+
+impl DataRequestDataType {
+    // @returns wether both outcomes should be treated as the same outcome for this data type
+    pub fn outcomes_match(&self, a: &Outcome, b: &Outcome) -> bool {
+        match (self, a, b) {
+            (
+                DataRequestDataType::Range(_, tolerance),
+                Outcome::Answer(AnswerType::Number(a)),
+                Outcome::Answer(AnswerType::Number(b)),
+            ) => {
+                let (a_value, b_value) = (u128::from(a.value), u128::from(b.value));
+                let difference = number_difference(a, b);
+
+                match tolerance {
+                    Tolerance::Absolute(max_difference) => {
+                        difference <= u128::from(*max_difference)
+                    }
+                    Tolerance::Relative(percentage) => {
+                        difference
+                            <= helpers::calc_product(
+                                std::cmp::max(a_value, b_value),
+                                u128::from(*percentage),
+                                u128::from(PERCENTAGE_DIVISOR),
+                            )
+                    }
+                }
+            }
+            _ => a == b,
+        }
+    }
+
+    // @returns how far apart two matching outcomes are, only number answers of a `Range` can match without being equal
+    pub fn outcome_difference(&self, a: &Outcome, b: &Outcome) -> u128 {
+        match (self, a, b) {
+            (
+                DataRequestDataType::Range(_, _),
+                Outcome::Answer(AnswerType::Number(a)),
+                Outcome::Answer(AnswerType::Number(b)),
+            ) => number_difference(a, b),
+            _ => 0,
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub enum DeliveryStatus {
+    Pending,   // `set_outcome` was sent to the requester and awaits its result
+    Delivered, // requester accepted the outcome
+    Failed,    // requester's `set_outcome` failed, delivery can be retried
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
@@ -46,8 +128,19 @@ pub struct DataRequest {
     pub request_config: DataRequestConfig,  // Config enforced by global parameters
     pub initial_challenge_period: Duration, // challenge period for first resolution
     pub final_arbitrator_triggered: bool,
+    pub final_arbitrator_deadline: Option<Timestamp>, // time after which the fallback can be triggered if the final arbitrator didn't finalize
+    pub refund_all_stakes: bool, // wether claims return every round's stake instead of paying out correct stakers
+    pub unbonded_final_outcome: bool, // wether the final arbitrator chose an outcome that no round bonded
     pub tags: Vec<String>,
     pub data_type: DataRequestDataType,
+    pub delivery_status: Option<DeliveryStatus>, // status of delivering the finalized outcome to the requester
+    pub delivery_sent_at: Option<Timestamp>, // time the finalized outcome was last sent to the requester
+    pub payment_token: AccountId,            // token the fee and validity bond were paid in
+    pub reveal_period: Option<Duration>, // duration of each round's reveal phase when stakes are committed before they are revealed
+    pub claimed: LookupSet<AccountId>,   // accounts that already claimed their payout
+    pub cancelled: bool, // wether the requester or creator withdrew the request before anyone staked
+    pub settlement_time: Option<Timestamp>, // time before which the request can't be staked on, the first round opens with the first stake after it
+    pub subscription_id: Option<u64>,       // subscription that prepaid this request
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
@@ -57,7 +150,15 @@ pub struct DataRequestConfig {
     final_arbitrator: AccountId,
     validity_bond: Balance,
     pub paid_fee: Balance,
+    resolution_bond_base: Balance, // larger of the validity bond and fee denominated in the config's `payment_token`, whichever token paid them
     pub stake_multiplier: Option<u16>,
+    pub expiry_time: Timestamp, // time after which the request can be expired if it has no bonded outcome
+    pub treasury_share: u16,    // share of incorrectly staked tokens routed to the treasury
+    pub burn_share: u16,        // share of incorrectly staked tokens that's burned
+    invoke_final_arbitrator_contract: bool, // wether `final_arbitrator` is called when it's invoked
+    final_arbitrator_deadline: Duration,
+    final_arbitrator_fallback: oracle_config::FinalArbitratorFallback,
+    unbonded_outcome_policy: oracle_config::UnbondedOutcomePolicy,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
@@ -74,8 +175,41 @@ pub struct DataRequestSummary {
     pub global_config_id: U64,
     pub initial_challenge_period: U64,
     pub final_arbitrator_triggered: bool,
+    pub final_arbitrator_deadline: Option<U64>,
+    pub refund_all_stakes: bool,
+    pub unbonded_final_outcome: bool,
+    pub cancelled: bool,
+    pub settlement_time: Option<WrappedTimestamp>,
+    pub subscription_id: Option<U64>,
     pub tags: Vec<String>,
     pub data_type: DataRequestDataType,
+    pub delivery_status: Option<DeliveryStatus>,
+    pub payment_token: AccountId,
+    pub reveal_period: Option<U64>,
+}
+
+struct Payout {
+    total_correct_staked: Balance,
+    total_incorrect_staked: Balance,
+    user_correct_stake: Balance,
+    stake_profit: Balance,
+    fee_profit: Balance,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ClaimableSummary {
+    pub stake_token: AccountId,
+    pub stake_token_payout: WrappedBalance,
+    pub payment_token: AccountId,
+    pub payment_token_payout: WrappedBalance,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AccountStakeSummary {
+    pub request_id: U64,
+    pub round: u16,
+    pub outcome: Outcome,
+    pub stake: WrappedBalance,
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
@@ -83,6 +217,9 @@ pub struct DataRequestConfigSummary {
     pub validity_bond: WrappedBalance,
     pub paid_fee: WrappedBalance,
     pub stake_multiplier: Option<u16>,
+    pub expiry_time: WrappedTimestamp,
+    pub treasury_share: u16,
+    pub burn_share: u16,
 }
 
 trait DataRequestChange {
@@ -91,10 +228,24 @@ trait DataRequestChange {
         id: u64,
         global_config_id: u64,
         global_config: &oracle_config::OracleConfig,
+        payment_token: AccountId,
+        validity_bond: Balance,
         paid_fee: Balance,
+        resolution_fee: Balance,
         request_data: NewDataRequestArgs,
     ) -> Self;
+    fn open_first_window(&mut self);
     fn stake(&mut self, sender: AccountId, outcome: Outcome, amount: Balance) -> Balance;
+    fn stake_in_round(
+        &mut self,
+        round: u16,
+        sender: AccountId,
+        outcome: Outcome,
+        amount: Balance,
+    ) -> Balance;
+    fn commit(&mut self, sender: AccountId, hash: Vec<u8>, amount: Balance);
+    fn reveal(&mut self, sender: AccountId, round: u16, outcome: Outcome, salt: String) -> Balance;
+    fn withdraw_unrevealed(&mut self, account_id: AccountId, round: u16) -> Balance;
     fn unstake(
         &mut self,
         sender: AccountId,
@@ -103,10 +254,15 @@ trait DataRequestChange {
         amount: Balance,
     ) -> Balance;
     fn finalize(&mut self);
+    fn expire(&mut self);
+    fn cancel(&mut self);
     fn invoke_final_arbitrator(&mut self, bond_size: Balance) -> bool;
     fn finalize_final_arbitrator(&mut self, outcome: Outcome);
+    fn finalize_unbonded_outcome(&mut self);
+    fn request_arbitration(&self) -> Promise;
+    fn deliver_outcome(&mut self) -> Promise;
     fn claim(&mut self, account_id: String) -> ClaimRes;
-    fn return_validity_bond(&self, token: AccountId) -> PromiseOrValue<bool>;
+    fn return_validity_bond(&self) -> PromiseOrValue<bool>;
 }
 
 impl DataRequestChange for DataRequest {
@@ -115,10 +271,14 @@ impl DataRequestChange for DataRequest {
         id: u64,
         global_config_id: u64,
         config: &oracle_config::OracleConfig,
+        payment_token: AccountId,
+        validity_bond: Balance,
         paid_fee: Balance,
+        resolution_fee: Balance,
         request_data: NewDataRequestArgs,
     ) -> Self {
         let resolution_windows = Vector::new(format!("rw{}", id).as_bytes().to_vec());
+        let settlement_time = request_data.settlement_time.map(u64::from);
 
         Self {
             id,
@@ -132,58 +292,141 @@ impl DataRequestChange for DataRequest {
                 default_challenge_window_duration: config.default_challenge_window_duration.into(),
                 final_arbitrator_invoke_amount: config.final_arbitrator_invoke_amount.into(),
                 final_arbitrator: config.final_arbitrator.to_string(),
-                validity_bond: config.validity_bond.into(),
+                validity_bond,
                 stake_multiplier: requester.stake_multiplier,
                 paid_fee,
+                // the resolution bond is staked in `stake_token` so it's based on amounts in one token for every request
+                resolution_bond_base: std::cmp::max(
+                    u128::from(config.validity_bond),
+                    resolution_fee,
+                ),
+                // requests can't be staked on before settlement so they only start expiring from then
+                expiry_time: settlement_time.unwrap_or(env::block_timestamp())
+                    + u64::from(config.request_expiry_duration),
+                treasury_share: config.treasury_share,
+                burn_share: config.burn_share,
+                invoke_final_arbitrator_contract: config.invoke_final_arbitrator_contract,
+                final_arbitrator_deadline: config.final_arbitrator_deadline.into(),
+                final_arbitrator_fallback: config.final_arbitrator_fallback.clone(),
+                unbonded_outcome_policy: config.unbonded_outcome_policy.clone(),
             },
             initial_challenge_period: request_data.challenge_period.into(),
             final_arbitrator_triggered: false,
+            final_arbitrator_deadline: None,
+            refund_all_stakes: false,
+            unbonded_final_outcome: false,
             description: request_data.description,
             tags: request_data.tags,
             data_type: request_data.data_type,
+            reveal_period: request_data.reveal_period.map(u64::from),
+            claimed: LookupSet::new(format!("cl{}", id).as_bytes().to_vec()),
             creator: request_data.creator,
+            delivery_status: None,
+            delivery_sent_at: None,
+            payment_token,
+            cancelled: false,
+            settlement_time,
+            subscription_id: None,
         }
     }
 
-    // @returns amount of tokens that didn't get staked
-    fn stake(&mut self, sender: AccountId, outcome: Outcome, amount: Balance) -> Balance {
-        let mut window = self.resolution_windows.iter().last().unwrap_or_else(|| {
-            ResolutionWindow::new(
+    // @notice Creates round 0 on the first stake or commit, starting at the settlement time so its schedule doesn't depend on when it's first staked on
+    fn open_first_window(&mut self) {
+        if self.resolution_windows.len() == 0 {
+            self.resolution_windows.push(&ResolutionWindow::new(
                 self.id,
                 0,
                 self.calc_resolution_bond(),
                 self.initial_challenge_period,
-                env::block_timestamp(),
-            )
-        });
+                self.reveal_period,
+                self.settlement_time.unwrap_or_else(env::block_timestamp),
+            ));
+        }
+    }
 
-        let unspent = window.stake(sender, outcome, amount);
+    // @returns amount of tokens that didn't get staked
+    fn stake(&mut self, sender: AccountId, outcome: Outcome, amount: Balance) -> Balance {
+        self.open_first_window();
 
-        // If first window push it to vec, else replace updated window struct
-        if self.resolution_windows.len() == 0 {
-            self.resolution_windows.push(&window);
-        } else {
-            self.resolution_windows.replace(
-                self.resolution_windows.len() - 1, // Last window
-                &window,
-            );
-        }
+        let current_round = self.resolution_windows.len() as u16 - 1;
+        self.stake_in_round(current_round, sender, outcome, amount)
+    }
+
+    // @returns amount of tokens that didn't get staked in `round`, which is the current round unless a commitment of an earlier round is revealed
+    fn stake_in_round(
+        &mut self,
+        round: u16,
+        sender: AccountId,
+        outcome: Outcome,
+        amount: Balance,
+    ) -> Balance {
+        let mut window = self
+            .resolution_windows
+            .get(round as u64)
+            .expect("ResolutionWindow with this round does not exist");
+        let was_bonded = window.bonded_outcome.is_some();
 
-        // Check if this stake is bonded for the current window and if the final arbitrator should be invoked.
+        let outcome = window.canonical_outcome(outcome, &self.data_type);
+        let unspent = window.stake(sender, outcome, amount);
+        self.resolution_windows.replace(round as u64, &window);
+
+        // Check if this stake bonded the window and if the final arbitrator should be invoked.
         // If the final arbitrator is invoked other stake won't come through.
-        if window.bonded_outcome.is_some() && !self.invoke_final_arbitrator(window.bond_size) {
-            self.resolution_windows.push(&ResolutionWindow::new(
-                self.id,
-                self.resolution_windows.len() as u16,
-                window.bond_size,
-                self.request_config.default_challenge_window_duration,
-                env::block_timestamp(),
-            ));
+        if !was_bonded && window.bonded_outcome.is_some() {
+            if !self.invoke_final_arbitrator(window.bond_size) {
+                // With commit-reveal the bonded round stays open for reveals until its end, so the next round starts after it
+                let start_time = match self.reveal_period {
+                    Some(_) => window.end_time,
+                    None => env::block_timestamp(),
+                };
+                self.resolution_windows.push(&ResolutionWindow::new(
+                    self.id,
+                    self.resolution_windows.len() as u16,
+                    window.bond_size,
+                    self.request_config.default_challenge_window_duration,
+                    self.reveal_period,
+                    start_time,
+                ));
+            } else if self.request_config.invoke_final_arbitrator_contract {
+                self.request_arbitration();
+            }
         }
 
         unspent
     }
 
+    fn commit(&mut self, sender: AccountId, hash: Vec<u8>, amount: Balance) {
+        self.open_first_window();
+
+        let mut window = self.resolution_windows.iter().last().unwrap();
+        window.commit(sender, hash, amount);
+    }
+
+    // @returns amount of tokens that didn't get staked in the round of the commitment
+    fn reveal(&mut self, sender: AccountId, round: u16, outcome: Outcome, salt: String) -> Balance {
+        let mut window = self
+            .resolution_windows
+            .get(round as u64)
+            .expect("ResolutionWindow with this round does not exist");
+        let amount = window.reveal(&sender, &outcome, &salt);
+
+        self.stake_in_round(round, sender, outcome, amount)
+    }
+
+    // @returns amount of tokens committed in a round that can no longer be revealed
+    fn withdraw_unrevealed(&mut self, account_id: AccountId, round: u16) -> Balance {
+        let mut window = self
+            .resolution_windows
+            .get(round as u64)
+            .expect("ResolutionWindow with this round does not exist");
+        assert!(
+            self.finalized_outcome.is_some() || env::block_timestamp() >= window.end_time,
+            "Commitment can still be revealed"
+        );
+
+        window.withdraw_unrevealed(&account_id)
+    }
+
     // @returns amount of tokens that didn't get staked
     fn unstake(
         &mut self,
@@ -196,19 +439,38 @@ impl DataRequestChange for DataRequest {
             .resolution_windows
             .get(round as u64)
             .expect("ERR_NO_RESOLUTION_WINDOW");
+        let is_current_window = round as u64 == self.resolution_windows.len() - 1;
+        assert!(
+            is_current_window || window.bonded_outcome.is_none(),
+            "Can only unstake from the current or a non-bonded resolution window"
+        );
+
+        let outcome = window.canonical_outcome(outcome, &self.data_type);
+        let unstaked = window.unstake(sender, outcome, amount);
+        self.resolution_windows.replace(round as u64, &window);
 
-        window.unstake(sender, outcome, amount)
+        unstaked
     }
 
     fn finalize(&mut self) {
         self.finalized_outcome = self.get_final_outcome();
     }
 
+    fn expire(&mut self) {
+        self.finalized_outcome = Some(Outcome::Invalid);
+    }
+
+    fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+
     // @returns wether final arbitrator was triggered
     fn invoke_final_arbitrator(&mut self, bond_size: Balance) -> bool {
         let should_invoke = bond_size >= self.request_config.final_arbitrator_invoke_amount;
         if should_invoke {
-            self.final_arbitrator_triggered = true
+            self.final_arbitrator_triggered = true;
+            self.final_arbitrator_deadline =
+                Some(env::block_timestamp() + self.request_config.final_arbitrator_deadline);
         }
         self.final_arbitrator_triggered
     }
@@ -217,76 +479,123 @@ impl DataRequestChange for DataRequest {
         self.finalized_outcome = Some(outcome);
     }
 
+    // @notice Settles every round according to the `unbonded_outcome_policy` since no round bonded the final outcome
+    fn finalize_unbonded_outcome(&mut self) {
+        self.unbonded_final_outcome = true;
+        self.refund_all_stakes = self.request_config.unbonded_outcome_policy
+            == oracle_config::UnbondedOutcomePolicy::Refund;
+    }
+
+    // @notice Calls the final arbitrator contract with the outcomes bonded in the last two rounds, its answer is handled in `dr_final_arbitrator_callback`
+    fn request_arbitration(&self) -> Promise {
+        let rounds = self.resolution_windows.len();
+        let contested_outcomes = (rounds.saturating_sub(2)..rounds)
+            .filter_map(|round| self.resolution_windows.get(round).unwrap().bonded_outcome)
+            .collect();
+
+        final_arbitrator::arbitrate(
+            &self.request_config.final_arbitrator,
+            self.summarize_dr(),
+            contested_outcomes,
+        )
+        .then(ext_self::dr_final_arbitrator_callback(
+            U64(self.id),
+            // NEAR params
+            &env::current_account_id(),
+            0,
+            GAS_FINAL_ARBITRATOR_CALLBACK,
+        ))
+    }
+
+    // @notice Sends the finalized outcome to the requester, the result is verified in `dr_proceed_finalization`
+    fn deliver_outcome(&mut self) -> Promise {
+        self.delivery_status = Some(DeliveryStatus::Pending);
+        self.delivery_sent_at = Some(env::block_timestamp());
+
+        self.requester
+            .set_outcome(
+                self.finalized_outcome.clone().unwrap(),
+                self.tags.clone(),
+                self.final_arbitrator_triggered,
+            )
+            .then(ext_self::dr_proceed_finalization(
+                U64(self.id),
+                // NEAR params
+                &env::current_account_id(),
+                0,
+                GAS_PROCEED_FINALIZATION,
+            ))
+    }
+
     fn claim(&mut self, account_id: String) -> ClaimRes {
-        // Metrics for calculating payout
-        let mut total_correct_staked = 0;
-        let mut total_incorrect_staked = 0;
-        let mut user_correct_stake = 0;
+        assert!(
+            self.claimed.insert(&account_id),
+            "{} already claimed DataRequest {}",
+            account_id,
+            self.id
+        );
+
+        let reward = self.calc_unbonded_outcome_reward(&account_id);
+
+        if self.refund_all_stakes {
+            let mut refund = 0;
+            for round in 0..self.resolution_windows.len() {
+                let mut window = self.resolution_windows.get(round).unwrap();
+                refund += window.claim_all_for(&account_id);
+                self.resolution_windows.replace(round, &window);
+            }
+
+            logger::log_claim(&account_id, self.id, 0, 0, 0, 0, reward, refund);
+            return ClaimRes {
+                payment_token_payout: reward,
+                stake_token_payout: refund,
+            };
+        }
+        let correct_outcome =
+            self.get_correct_bonded_outcome(self.finalized_outcome.as_ref().unwrap());
+        let mut stake_results = Vec::new();
+        let mut refund = 0;
 
         // For any round after the resolution round handle generically
         for round in 0..self.resolution_windows.len() {
             let mut window = self.resolution_windows.get(round).unwrap();
-            let stake_state: WindowStakeResult = window.claim_for(
-                account_id.to_string(),
-                self.finalized_outcome.as_ref().unwrap(),
-            );
-            match stake_state {
-                WindowStakeResult::Correct(correctly_staked) => {
-                    total_correct_staked += correctly_staked.bonded_stake;
-                    user_correct_stake += correctly_staked.user_stake;
-                }
-                WindowStakeResult::Incorrect(incorrectly_staked) => {
-                    total_incorrect_staked += incorrectly_staked
-                }
-                WindowStakeResult::NoResult => (),
-            }
+            stake_results.push(window.claim_for(account_id.to_string(), &correct_outcome));
+            // Stake on outcomes that never filled a round didn't take part in resolution and is returned
+            refund += window.claim_unbonded_for(&account_id);
 
             self.resolution_windows.replace(round as u64, &window);
         }
 
-        let stake_profit = match total_correct_staked {
-            0 => 0,
-            _ => helpers::calc_product(
-                user_correct_stake,
-                total_incorrect_staked,
-                total_correct_staked,
-            ),
-        };
-
-        let fee_profit = match total_correct_staked {
-            0 => 0,
-            _ => helpers::calc_product(
-                user_correct_stake,
-                self.request_config.paid_fee,
-                total_correct_staked,
-            ),
-        };
+        let payout = self.calc_payout(stake_results);
 
         logger::log_claim(
             &account_id,
             self.id,
-            total_correct_staked,
-            total_incorrect_staked,
-            user_correct_stake,
-            stake_profit,
-            fee_profit,
+            payout.total_correct_staked,
+            payout.total_incorrect_staked,
+            payout.user_correct_stake,
+            payout.stake_profit,
+            payout.fee_profit + reward,
+            refund,
         );
 
         ClaimRes {
-            payment_token_payout: fee_profit,
-            stake_token_payout: user_correct_stake + stake_profit,
+            payment_token_payout: payout.fee_profit + reward,
+            stake_token_payout: payout.user_correct_stake + payout.stake_profit + refund,
         }
     }
 
-    // @notice Return what's left of validity_bond to requester
-    fn return_validity_bond(&self, token: AccountId) -> PromiseOrValue<bool> {
-        match self.finalized_outcome.as_ref().unwrap() {
-            Outcome::Answer(_) => PromiseOrValue::Promise(fungible_token_transfer(
-                token,
+    // @notice Return what's left of validity_bond and fee to the creator, see `calc_creator_refund`
+    fn return_validity_bond(&self) -> PromiseOrValue<bool> {
+        let refund = self.calc_creator_refund();
+        if refund > 0 {
+            PromiseOrValue::Promise(fungible_token_transfer(
+                self.payment_token.clone(),
                 self.creator.clone(),
-                self.request_config.validity_bond,
-            )),
-            Outcome::Invalid => PromiseOrValue::Value(false),
+                refund,
+            ))
+        } else {
+            PromiseOrValue::Value(false)
         }
     }
 }
@@ -294,15 +603,30 @@ impl DataRequestChange for DataRequest {
 trait DataRequestView {
     fn assert_valid_outcome(&self, outcome: &Outcome);
     fn assert_valid_outcome_type(&self, outcome: &Outcome);
-    fn assert_can_stake_on_outcome(&self, outcome: &Outcome);
+    fn assert_can_stake_on_outcome(&self, round: u16, outcome: &Outcome);
     fn assert_not_finalized(&self);
     fn assert_finalized(&self);
     fn assert_can_finalize(&self);
+    fn assert_can_expire(&self);
+    fn assert_can_cancel(&self);
+    fn assert_settled(&self);
     fn assert_final_arbitrator(&self);
     fn assert_final_arbitrator_invoked(&self);
     fn assert_final_arbitrator_not_invoked(&self);
+    fn assert_final_arbitrator_deadline_passed(&self);
+    fn assert_commit_reveal(&self);
+    fn assert_not_commit_reveal(&self);
     fn get_final_outcome(&self) -> Option<Outcome>;
+    fn is_bonded_outcome(&self, outcome: &Outcome) -> bool;
+    fn get_correct_bonded_outcome(&self, final_outcome: &Outcome) -> Option<Outcome>;
     fn calc_resolution_bond(&self) -> Balance;
+    fn calc_payout(&self, stake_results: Vec<WindowStakeResult>) -> Payout;
+    fn calc_slashed_shares(&self, total_incorrect_staked: Balance) -> (Balance, Balance);
+    fn calc_slashed_stake(&self) -> (Balance, Balance);
+    fn calc_stake_on_final_outcome(&self, account_id: Option<&AccountId>) -> Balance;
+    fn calc_unbonded_outcome_reward(&self, account_id: &AccountId) -> Balance;
+    fn calc_creator_refund(&self) -> Balance;
+    fn claimable(&self, account_id: &AccountId) -> ClaimRes;
     fn summarize_dr(&self) -> DataRequestSummary;
 }
 
@@ -333,9 +657,26 @@ impl DataRequestView for DataRequest {
                     DataRequestDataType::String,
                     "ERR_WRONG_OUTCOME_TYPE"
                 ),
-                AnswerType::Number(ans_num) => match self.data_type {
-                    DataRequestDataType::Number(dr_multiplier) => {
-                        assert_eq!(dr_multiplier, ans_num.multiplier, "ERR_WRONG_MULTIPLIER")
+                AnswerType::Number(ans_num) => match &self.data_type {
+                    DataRequestDataType::Number(dr_multiplier)
+                    | DataRequestDataType::Range(dr_multiplier, _) => {
+                        assert_eq!(*dr_multiplier, ans_num.multiplier, "ERR_WRONG_MULTIPLIER")
+                    }
+                    _ => panic!("ERR_WRONG_OUTCOME_TYPE"),
+                },
+                AnswerType::Vector(ans_nums) => match &self.data_type {
+                    DataRequestDataType::Vector(dr_multiplier) => {
+                        assert_eq!(
+                            ans_nums.len(),
+                            self.sources.len(),
+                            "ERR_WRONG_VECTOR_LENGTH"
+                        );
+                        assert!(
+                            ans_nums
+                                .iter()
+                                .all(|ans_num| ans_num.multiplier == *dr_multiplier),
+                            "ERR_WRONG_MULTIPLIER"
+                        );
                     }
                     _ => panic!("ERR_WRONG_OUTCOME_TYPE"),
                 },
@@ -344,21 +685,20 @@ impl DataRequestView for DataRequest {
         }
     }
 
-    fn assert_can_stake_on_outcome(&self, outcome: &Outcome) {
-        if self.resolution_windows.len() > 1 {
-            let last_window = self
-                .resolution_windows
-                .get(self.resolution_windows.len() - 2)
-                .unwrap();
-            assert_ne!(
-                &last_window.bonded_outcome.unwrap(),
-                outcome,
+    fn assert_can_stake_on_outcome(&self, round: u16, outcome: &Outcome) {
+        if round > 0 {
+            let prev_window = self.resolution_windows.get(round as u64 - 1).unwrap();
+            assert!(
+                !self
+                    .data_type
+                    .outcomes_match(&prev_window.bonded_outcome.unwrap(), outcome),
                 "Outcome is incompatible for this round"
             );
         }
     }
 
     fn assert_not_finalized(&self) {
+        assert!(!self.cancelled, "DataRequest is cancelled");
         assert!(
             self.finalized_outcome.is_none(),
             "Can't stake in finalized DataRequest"
@@ -389,6 +729,55 @@ impl DataRequestView for DataRequest {
         self.assert_not_finalized();
     }
 
+    fn assert_can_expire(&self) {
+        self.assert_not_finalized();
+        // a round 0 bond can invoke the final arbitrator without opening a second round
+        self.assert_final_arbitrator_not_invoked();
+        assert!(
+            self.resolution_windows.len() < 2
+                && self
+                    .resolution_windows
+                    .get(0)
+                    .map_or(true, |window| window.bonded_outcome.is_none()),
+            "DataRequest with a bonded outcome can't expire"
+        );
+        assert!(
+            env::block_timestamp() >= self.request_config.expiry_time,
+            "DataRequest can only be expired after {}",
+            self.request_config.expiry_time
+        );
+        if let Some(window) = self.resolution_windows.get(0) {
+            assert!(
+                env::block_timestamp() >= window.end_time,
+                "Error can only be expired after the first resolution window has timed out"
+            );
+        }
+    }
+
+    fn assert_can_cancel(&self) {
+        let sender = env::predecessor_account_id();
+        assert!(
+            sender == self.requester.account_id || sender == self.creator,
+            "Only the requester or creator can cancel this DataRequest"
+        );
+        self.assert_not_finalized();
+        assert_eq!(
+            self.resolution_windows.len(),
+            0,
+            "DataRequest that has been staked on can't be cancelled"
+        );
+    }
+
+    fn assert_settled(&self) {
+        if let Some(settlement_time) = self.settlement_time {
+            assert!(
+                env::block_timestamp() >= settlement_time,
+                "DataRequest can only be staked on after {}",
+                settlement_time
+            );
+        }
+    }
+
     fn assert_final_arbitrator(&self) {
         assert_eq!(
             self.request_config.final_arbitrator,
@@ -406,6 +795,17 @@ impl DataRequestView for DataRequest {
         );
     }
 
+    fn assert_final_arbitrator_deadline_passed(&self) {
+        let deadline = self
+            .final_arbitrator_deadline
+            .expect("Final arbitrator is not invoked");
+        assert!(
+            env::block_timestamp() >= deadline,
+            "Final arbitrator has until {} to finalize",
+            deadline
+        );
+    }
+
     fn assert_final_arbitrator_not_invoked(&self) {
         assert!(
             !self.final_arbitrator_triggered,
@@ -414,6 +814,20 @@ impl DataRequestView for DataRequest {
         );
     }
 
+    fn assert_commit_reveal(&self) {
+        assert!(
+            self.reveal_period.is_some(),
+            "DataRequest does not use commit-reveal"
+        );
+    }
+
+    fn assert_not_commit_reveal(&self) {
+        assert!(
+            self.reveal_period.is_none(),
+            "Stakes on this DataRequest need to be committed and revealed"
+        );
+    }
+
     fn get_final_outcome(&self) -> Option<Outcome> {
         assert!(
             self.resolution_windows.iter().count() >= 2,
@@ -424,17 +838,39 @@ impl DataRequestView for DataRequest {
         last_bonded_window.bonded_outcome
     }
 
+    // @returns wether any round bonded `outcome`, or an outcome within its tolerance
+    fn is_bonded_outcome(&self, outcome: &Outcome) -> bool {
+        self.resolution_windows
+            .iter()
+            .any(|window| match &window.bonded_outcome {
+                Some(bonded_outcome) => self.data_type.outcomes_match(bonded_outcome, outcome),
+                None => false,
+            })
+    }
+
+    /**
+     * @notice Tolerance isn't transitive so rounds that bonded opposing outcomes can both be within the tolerance of the final outcome,
+     * only the bonded outcome closest to it is correct, the earliest one if several are equally close
+     * @returns the bonded outcome whose rounds are paid out as correct, `None` if no round bonded the final outcome
+     */
+    fn get_correct_bonded_outcome(&self, final_outcome: &Outcome) -> Option<Outcome> {
+        self.resolution_windows
+            .iter()
+            .filter_map(|window| window.bonded_outcome)
+            .filter(|bonded_outcome| self.data_type.outcomes_match(bonded_outcome, final_outcome))
+            .min_by_key(|bonded_outcome| {
+                self.data_type
+                    .outcome_difference(bonded_outcome, final_outcome)
+            })
+    }
+
     /**
      * @notice Calculates the size of the resolution bond. If the accumulated fee is smaller than the validity bond, we payout the validity bond to validators, thus they have to stake double in order to be
      * eligible for the reward, in the case that the fee is greater than the validity bond validators need to have a cumulative stake of double the fee amount
      * @returns The size of the initial `resolution_bond` denominated in `stake_token`
      */
     fn calc_resolution_bond(&self) -> Balance {
-        let base_bond = if self.request_config.paid_fee >= self.request_config.validity_bond {
-            self.request_config.paid_fee
-        } else {
-            self.request_config.validity_bond
-        };
+        let base_bond = self.request_config.resolution_bond_base;
 
         env::log(
             format!(
@@ -448,63 +884,314 @@ impl DataRequestView for DataRequest {
     }
 
     /**
-     * @notice Transforms a data request struct into another struct with Serde serialization
+     * @notice Calculates an account's share of the slashed incorrect stake and the fee from its stake on correctly bonded rounds
+     * @returns the account's `Payout`, correct stake is denominated in `stake_token` and the fee profit in `payment_token`
      */
-    fn summarize_dr(&self) -> DataRequestSummary {
-        // format resolution windows inside this data request
-        let mut resolution_windows = Vec::new();
-        for i in self.resolution_windows.iter() {
-            let rw = ResolutionWindowSummary {
-                round: i.round,
-                start_time: U64(i.start_time),
-                end_time: U64(i.end_time),
-                bond_size: U128(i.bond_size),
-                bonded_outcome: i.bonded_outcome,
-            };
-            resolution_windows.push(rw);
-        }
+    fn calc_payout(&self, stake_results: Vec<WindowStakeResult>) -> Payout {
+        // Metrics for calculating payout
+        let mut total_correct_staked = 0;
+        let mut total_incorrect_staked = 0;
+        let mut user_correct_stake = 0;
 
-        // format data request
-        DataRequestSummary {
-            id: self.id,
-            description: self.description.clone(),
-            sources: self.sources.clone(),
-            outcomes: self.outcomes.clone(),
-            requester: self.requester.clone(),
-            creator: self.creator.clone(),
-            finalized_outcome: self.finalized_outcome.clone(),
-            resolution_windows: resolution_windows,
-            global_config_id: U64(self.global_config_id),
-            initial_challenge_period: U64(self.initial_challenge_period),
-            final_arbitrator_triggered: self.final_arbitrator_triggered,
-            tags: self.tags.clone(),
-            data_type: self.data_type.clone(),
-            request_config: DataRequestConfigSummary {
-                validity_bond: U128(self.request_config.validity_bond),
-                paid_fee: U128(self.request_config.paid_fee),
-                stake_multiplier: self.request_config.stake_multiplier,
-            },
+        for stake_result in stake_results {
+            match stake_result {
+                WindowStakeResult::Correct(correctly_staked) => {
+                    total_correct_staked += correctly_staked.bonded_stake;
+                    user_correct_stake += correctly_staked.user_stake;
+                }
+                WindowStakeResult::Incorrect(incorrectly_staked) => {
+                    total_incorrect_staked += incorrectly_staked
+                }
+                WindowStakeResult::NoResult => (),
+            }
         }
-    }
-}
-
-#[near_bindgen]
-impl Contract {
-    pub fn dr_exists(&self, id: U64) -> bool {
-        self.data_requests.get(id.into()).is_some()
-    }
 
-    // Merge config and payload
-    pub fn dr_new(
-        &mut self,
-        sender: AccountId,
-        amount: Balance,
+        // Part of the incorrect stake goes to the treasury or is burned, the rest is divided among correct stakers
+        let (treasury_amount, burn_amount) = self.calc_slashed_shares(total_incorrect_staked);
+        let stake_profit = match total_correct_staked {
+            0 => 0,
+            _ => helpers::calc_product(
+                user_correct_stake,
+                total_incorrect_staked - treasury_amount - burn_amount,
+                total_correct_staked,
+            ),
+        };
+
+        let fee_profit = match total_correct_staked {
+            0 => 0,
+            _ => helpers::calc_product(
+                user_correct_stake,
+                self.request_config.paid_fee,
+                total_correct_staked,
+            ),
+        };
+
+        Payout {
+            total_correct_staked,
+            total_incorrect_staked,
+            user_correct_stake,
+            stake_profit,
+            fee_profit,
+        }
+    }
+
+    // @returns the treasury's and the burned share of `total_incorrect_staked`
+    fn calc_slashed_shares(&self, total_incorrect_staked: Balance) -> (Balance, Balance) {
+        (
+            helpers::calc_product(
+                total_incorrect_staked,
+                u128::from(self.request_config.treasury_share),
+                u128::from(PERCENTAGE_DIVISOR),
+            ),
+            helpers::calc_product(
+                total_incorrect_staked,
+                u128::from(self.request_config.burn_share),
+                u128::from(PERCENTAGE_DIVISOR),
+            ),
+        )
+    }
+
+    // @returns the treasury's and the burned share of all stake on bonded outcomes that turned out incorrect
+    fn calc_slashed_stake(&self) -> (Balance, Balance) {
+        if self.refund_all_stakes {
+            return (0, 0);
+        }
+
+        let correct_outcome =
+            self.get_correct_bonded_outcome(self.finalized_outcome.as_ref().unwrap());
+        let total_incorrect_staked = self
+            .resolution_windows
+            .iter()
+            .map(|window| match &window.bonded_outcome {
+                Some(bonded_outcome) if correct_outcome.as_ref() != Some(bonded_outcome) => {
+                    window.bond_size
+                }
+                _ => 0,
+            })
+            .sum();
+
+        let (treasury_amount, burn_amount) = self.calc_slashed_shares(total_incorrect_staked);
+        if self.unbonded_final_outcome {
+            // There's no correct bonded stake to divide the rest among, so it goes to the treasury
+            return (total_incorrect_staked - burn_amount, burn_amount);
+        }
+
+        (treasury_amount, burn_amount)
+    }
+
+    // @returns the stake of `account_id`, or everyone's if `None`, on the final outcome across all rounds, counting outcomes within its tolerance
+    fn calc_stake_on_final_outcome(&self, account_id: Option<&AccountId>) -> Balance {
+        let final_outcome = self.finalized_outcome.as_ref().unwrap();
+        self.resolution_windows
+            .iter()
+            .map(|window| {
+                window
+                    .staked_outcomes
+                    .iter()
+                    .filter(|outcome| self.data_type.outcomes_match(outcome, final_outcome))
+                    .map(|outcome| match account_id {
+                        Some(account_id) => window.stake_on_outcome_for(account_id, &outcome),
+                        None => window.outcome_to_stake.get(&outcome).unwrap_or(0),
+                    })
+                    .sum::<Balance>()
+            })
+            .sum()
+    }
+
+    /**
+     * @notice Calculates the reward for staking the final outcome when only the final arbitrator chose it, stakers share the fee and,
+     * if the outcome is `Invalid`, the validity bond pro rata to their stake on it
+     * @returns the reward denominated in `payment_token`
+     */
+    fn calc_unbonded_outcome_reward(&self, account_id: &AccountId) -> Balance {
+        if !self.unbonded_final_outcome {
+            return 0;
+        }
+
+        let user_stake = self.calc_stake_on_final_outcome(Some(account_id));
+        if user_stake == 0 {
+            return 0;
+        }
+        let total_stake = self.calc_stake_on_final_outcome(None);
+
+        let reward = match self.finalized_outcome.as_ref().unwrap() {
+            Outcome::Answer(_) => self.request_config.paid_fee,
+            Outcome::Invalid => self.request_config.paid_fee + self.request_config.validity_bond,
+        };
+
+        helpers::calc_product(user_stake, reward, total_stake)
+    }
+
+    /**
+     * @notice Calculates what the creator gets back once finalized: the validity bond on an answer, nothing on `Invalid` since it's
+     * paid to the stakers of `Invalid`, unless the final arbitrator fallback refunded every stake or the final arbitrator chose an outcome
+     * nobody staked on, in which case nobody earned the bond or fee
+     * @returns the refund denominated in `payment_token`
+     */
+    fn calc_creator_refund(&self) -> Balance {
+        if self.unbonded_final_outcome && self.calc_stake_on_final_outcome(None) == 0 {
+            return self.request_config.validity_bond + self.request_config.paid_fee;
+        }
+
+        match self.finalized_outcome.as_ref().unwrap() {
+            Outcome::Answer(_) => self.request_config.validity_bond,
+            Outcome::Invalid if self.refund_all_stakes && !self.unbonded_final_outcome => {
+                self.request_config.validity_bond + self.request_config.paid_fee
+            }
+            Outcome::Invalid => 0,
+        }
+    }
+
+    // @returns what `claim` would pay out, using the currently bonded outcome if the request isn't finalized yet
+    fn claimable(&self, account_id: &AccountId) -> ClaimRes {
+        if self.claimed.contains(account_id) {
+            return ClaimRes {
+                payment_token_payout: 0,
+                stake_token_payout: 0,
+            };
+        }
+
+        let reward = match self.finalized_outcome {
+            Some(_) => self.calc_unbonded_outcome_reward(account_id),
+            None => 0,
+        };
+
+        if self.refund_all_stakes {
+            return ClaimRes {
+                payment_token_payout: reward,
+                stake_token_payout: self
+                    .resolution_windows
+                    .iter()
+                    .map(|window| window.total_stake_for(account_id))
+                    .sum(),
+            };
+        }
+
+        let final_outcome = if self.finalized_outcome.is_some() {
+            self.finalized_outcome.clone()
+        } else if self.final_arbitrator_triggered || self.resolution_windows.len() < 2 {
+            None
+        } else {
+            self.get_final_outcome()
+        };
+        let final_outcome = match final_outcome {
+            Some(outcome) => outcome,
+            None => {
+                return ClaimRes {
+                    payment_token_payout: 0,
+                    stake_token_payout: 0,
+                }
+            }
+        };
+        let correct_outcome = self.get_correct_bonded_outcome(&final_outcome);
+
+        let stake_results = self
+            .resolution_windows
+            .iter()
+            .map(|window| window.stake_result_for(account_id, &correct_outcome))
+            .collect();
+        let refund: Balance = self
+            .resolution_windows
+            .iter()
+            .map(|window| window.unbonded_stake_for(account_id))
+            .sum();
+        let payout = self.calc_payout(stake_results);
+
+        ClaimRes {
+            payment_token_payout: payout.fee_profit + reward,
+            stake_token_payout: payout.user_correct_stake + payout.stake_profit + refund,
+        }
+    }
+
+    /**
+     * @notice Transforms a data request struct into another struct with Serde serialization
+     */
+    fn summarize_dr(&self) -> DataRequestSummary {
+        // format resolution windows inside this data request
+        let mut resolution_windows = Vec::new();
+        for i in self.resolution_windows.iter() {
+            let rw = ResolutionWindowSummary {
+                round: i.round,
+                start_time: U64(i.start_time),
+                commit_end_time: i.commit_end_time.map(U64),
+                end_time: U64(i.end_time),
+                bond_size: U128(i.bond_size),
+                bonded_outcome: i.bonded_outcome,
+            };
+            resolution_windows.push(rw);
+        }
+
+        // format data request
+        DataRequestSummary {
+            id: self.id,
+            description: self.description.clone(),
+            sources: self.sources.clone(),
+            outcomes: self.outcomes.clone(),
+            requester: self.requester.clone(),
+            creator: self.creator.clone(),
+            finalized_outcome: self.finalized_outcome.clone(),
+            resolution_windows: resolution_windows,
+            global_config_id: U64(self.global_config_id),
+            initial_challenge_period: U64(self.initial_challenge_period),
+            final_arbitrator_triggered: self.final_arbitrator_triggered,
+            final_arbitrator_deadline: self.final_arbitrator_deadline.map(U64),
+            refund_all_stakes: self.refund_all_stakes,
+            unbonded_final_outcome: self.unbonded_final_outcome,
+            cancelled: self.cancelled,
+            settlement_time: self.settlement_time.map(U64),
+            subscription_id: self.subscription_id.map(U64),
+            tags: self.tags.clone(),
+            data_type: self.data_type.clone(),
+            reveal_period: self.reveal_period.map(U64),
+            delivery_status: self.delivery_status.clone(),
+            payment_token: self.payment_token.clone(),
+            request_config: DataRequestConfigSummary {
+                validity_bond: U128(self.request_config.validity_bond),
+                paid_fee: U128(self.request_config.paid_fee),
+                stake_multiplier: self.request_config.stake_multiplier,
+                expiry_time: U64(self.request_config.expiry_time),
+                treasury_share: self.request_config.treasury_share,
+                burn_share: self.request_config.burn_share,
+            },
+        }
+    }
+}
+
+// @notice Adds `amount` to the running total of `token`, so each token is transferred only once
+fn add_token_payout(payouts: &mut Vec<(AccountId, Balance)>, token: AccountId, amount: Balance) {
+    match payouts
+        .iter_mut()
+        .find(|(payout_token, _)| payout_token == &token)
+    {
+        Some((_, total)) => *total += amount,
+        None => payouts.push((token, amount)),
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn dr_exists(&self, id: U64) -> bool {
+        self.data_requests.get(id.into()).is_some()
+    }
+
+    // Merge config and payload
+    pub fn dr_new(
+        &mut self,
+        sender: AccountId,
+        amount: Balance,
         payload: NewDataRequestArgs,
     ) -> Balance {
+        self.assert_not_paused();
         let config = self.get_config();
-        let validity_bond: u128 = config.validity_bond.into();
         self.assert_whitelisted(sender.to_string());
-        self.assert_sender(&config.payment_token);
+        let requester = self.whitelist.whitelist_get_expect(&sender);
+        let (validity_bond, paid_fee) = config.calc_payment(
+            &env::predecessor_account_id(),
+            config.fee.calc_resolution_fee(
+                self.total_value_secured,
+                requester.get_total_value_secured(),
+            ),
+        );
         self.dr_validate(&payload);
         assert!(
             amount >= validity_bond,
@@ -513,23 +1200,25 @@ impl Contract {
             amount
         );
 
-        let paid_fee = amount - validity_bond;
+        assert!(
+            amount - validity_bond >= paid_fee,
+            "Resolution fee of {} not reached, received only {}",
+            paid_fee,
+            amount - validity_bond
+        );
 
-        let requester = self.whitelist.whitelist_get_expect(&sender);
-        let dr = DataRequest::new(
+        self.dr_insert(
             requester,
-            self.data_requests.len() as u64, // dr_id
-            self.configs.len() - 1,          // dr's config id
             &config,
+            env::predecessor_account_id(),
+            validity_bond,
             paid_fee,
             payload,
+            None,
         );
 
-        logger::log_new_data_request(&dr);
-
-        self.data_requests.push(&dr);
-
-        0
+        // refund anything paid on top of the validity bond and resolution fee
+        amount - validity_bond - paid_fee
     }
 
     #[payable]
@@ -539,22 +1228,129 @@ impl Contract {
         amount: Balance,
         payload: StakeDataRequestArgs,
     ) -> PromiseOrValue<WrappedBalance> {
+        self.assert_not_paused();
         let mut dr = self.dr_get_expect(payload.id.into());
         let config = self.configs.get(dr.global_config_id).unwrap();
+        let outcome = payload.outcome.canonicalize();
         self.assert_sender(&config.stake_token);
+        dr.assert_not_commit_reveal();
+        dr.assert_settled();
         dr.assert_final_arbitrator_not_invoked();
-        dr.assert_can_stake_on_outcome(&payload.outcome);
-        dr.assert_valid_outcome(&payload.outcome);
-        dr.assert_valid_outcome_type(&payload.outcome);
+        dr.assert_can_stake_on_outcome(
+            dr.resolution_windows.len().saturating_sub(1) as u16,
+            &outcome,
+        );
+        dr.assert_valid_outcome(&outcome);
+        dr.assert_valid_outcome_type(&outcome);
         dr.assert_not_finalized();
 
-        let unspent_stake = dr.stake(sender, payload.outcome, amount);
+        let unspent_stake = dr.stake(sender, outcome, amount);
         logger::log_update_data_request(&dr);
         self.data_requests.replace(payload.id.into(), &dr);
 
         PromiseOrValue::Value(U128(unspent_stake))
     }
 
+    /**
+     * @notice Computes the commitment `account_id` submits to commit to `outcome` in `round`, see `resolution_window::commitment_hash`
+     * @returns sha256 of the commitment preimage
+     */
+    pub fn dr_commitment_hash(
+        &self,
+        account_id: AccountId,
+        request_id: U64,
+        round: u16,
+        outcome: Outcome,
+        salt: String,
+    ) -> Base64VecU8 {
+        commitment_hash(
+            &account_id,
+            request_id.into(),
+            round,
+            &outcome.canonicalize(),
+            &salt,
+        )
+        .into()
+    }
+
+    /**
+     * @notice Commits stake to a hidden outcome, see `dr_commitment_hash` for how to compute the commitment
+     * @returns amount of tokens that didn't get committed
+     */
+    pub fn dr_commit(
+        &mut self,
+        sender: AccountId,
+        amount: Balance,
+        payload: CommitDataRequestArgs,
+    ) -> PromiseOrValue<WrappedBalance> {
+        self.assert_not_paused();
+        let mut dr = self.dr_get_expect(payload.id);
+        let config = self.configs.get(dr.global_config_id).unwrap();
+        self.assert_sender(&config.stake_token);
+        dr.assert_commit_reveal();
+        dr.assert_settled();
+        dr.assert_final_arbitrator_not_invoked();
+        dr.assert_not_finalized();
+
+        dr.commit(sender, payload.commitment.into(), amount);
+        logger::log_update_data_request(&dr);
+        self.data_requests.replace(payload.id.into(), &dr);
+
+        PromiseOrValue::Value(U128(0))
+    }
+
+    /**
+     * @notice Reveals the outcome committed to in `round`, the committed amount is staked on it in that round
+     */
+    #[payable]
+    pub fn dr_reveal(&mut self, request_id: U64, round: u16, outcome: Outcome, salt: String) {
+        let initial_storage = env::storage_usage();
+
+        let mut dr = self.dr_get_expect(request_id);
+        let config = self.configs.get(dr.global_config_id).unwrap();
+        let outcome = outcome.canonicalize();
+        dr.assert_commit_reveal();
+        dr.assert_final_arbitrator_not_invoked();
+        dr.assert_can_stake_on_outcome(round, &outcome);
+        dr.assert_valid_outcome(&outcome);
+        dr.assert_valid_outcome_type(&outcome);
+        dr.assert_not_finalized();
+
+        let unspent = dr.reveal(env::predecessor_account_id(), round, outcome, salt);
+        logger::log_update_data_request(&dr);
+        self.data_requests.replace(request_id.into(), &dr);
+
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+
+        if unspent > 0 {
+            fungible_token_transfer(config.stake_token, env::predecessor_account_id(), unspent);
+        }
+    }
+
+    /**
+     * @notice Settles a commitment of `account_id` that can no longer be revealed, it's returned to `account_id`
+     * unless the config forfeits unrevealed stakes in which case it's credited to the treasury
+     */
+    #[payable]
+    pub fn dr_withdraw_unrevealed(&mut self, request_id: U64, round: u16, account_id: AccountId) {
+        let initial_storage = env::storage_usage();
+
+        let mut dr = self.dr_get_expect(request_id);
+        let config = self.configs.get(dr.global_config_id).unwrap();
+        let amount = dr.withdraw_unrevealed(account_id.to_string(), round);
+        logger::log_update_data_request(&dr);
+
+        if config.forfeit_unrevealed_stakes {
+            self.accrue_slashed_stake(dr.id, config.stake_token.to_string(), amount, 0);
+        }
+
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+
+        if !config.forfeit_unrevealed_stakes {
+            fungible_token_transfer(config.stake_token, account_id, amount);
+        }
+    }
+
     #[payable]
     pub fn dr_unstake(
         &mut self,
@@ -569,10 +1365,11 @@ impl Contract {
         let unstaked = dr.unstake(
             env::predecessor_account_id(),
             resolution_round,
-            outcome,
+            outcome.canonicalize(),
             amount.into(),
         );
         let config = self.configs.get(dr.global_config_id).unwrap();
+        self.data_requests.replace(request_id.into(), &dr);
 
         helpers::refund_storage(initial_storage, env::predecessor_account_id());
         logger::log_update_data_request(&dr);
@@ -610,12 +1407,12 @@ impl Contract {
             // distribute fee + bond
             match prev_prom {
                 Some(p) => p.then(fungible_token_transfer(
-                    config.payment_token,
+                    dr.payment_token,
                     account_id,
                     stake_payout.payment_token_payout,
                 )),
                 None => fungible_token_transfer(
-                    config.payment_token,
+                    dr.payment_token,
                     account_id,
                     stake_payout.payment_token_payout,
                 ),
@@ -628,50 +1425,301 @@ impl Contract {
         }
     }
 
+    /**
+     * @notice Claims multiple `DataRequest`s at once, payouts are summed per token
+     * @returns promise chaining a single transfer per token
+     */
+    #[payable]
+    pub fn dr_claim_many(&mut self, account_id: String, request_ids: Vec<U64>) -> Promise {
+        let initial_storage = env::storage_usage();
+        let mut token_payouts: Vec<(AccountId, Balance)> = Vec::new();
+
+        for request_id in request_ids {
+            let mut dr = self.dr_get_expect(request_id);
+            dr.assert_finalized();
+            let stake_payout = dr.claim(account_id.to_string());
+            let config = self.configs.get(dr.global_config_id).unwrap();
+
+            logger::log_update_data_request(&dr);
+
+            add_token_payout(
+                &mut token_payouts,
+                config.stake_token,
+                stake_payout.stake_token_payout,
+            );
+            add_token_payout(
+                &mut token_payouts,
+                dr.payment_token,
+                stake_payout.payment_token_payout,
+            );
+        }
+
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+
+        token_payouts
+            .into_iter()
+            .filter(|(_, amount)| *amount > 0)
+            .fold(None, |prev_prom: Option<Promise>, (token, amount)| {
+                let transfer = fungible_token_transfer(token, account_id.to_string(), amount);
+                Some(match prev_prom {
+                    Some(p) => p.then(transfer),
+                    None => transfer,
+                })
+            })
+            .expect("can't claim 0")
+    }
+
     pub fn dr_finalize(&mut self, request_id: U64) {
         let mut dr = self.dr_get_expect(request_id.into());
         dr.assert_can_finalize();
-        let final_outcome = dr.get_final_outcome();
-
-        dr.requester
-            .set_outcome(final_outcome.unwrap(), dr.tags.clone(), false);
-
-        let config = self.configs.get(dr.global_config_id).unwrap();
 
         dr.finalize();
-        dr.return_validity_bond(config.payment_token);
+        dr.deliver_outcome();
+        dr.return_validity_bond();
 
+        let config = self.configs.get(dr.global_config_id).unwrap();
+        let (treasury_amount, burn_amount) = dr.calc_slashed_stake();
+        self.accrue_slashed_stake(dr.id, config.stake_token, treasury_amount, burn_amount);
         self.data_requests.replace(request_id.into(), &dr);
 
         logger::log_update_data_request(&dr);
     }
 
-    #[payable]
-    pub fn dr_final_arbitrator_finalize(
-        &mut self,
-        request_id: U64,
-        outcome: Outcome,
-    ) -> PromiseOrValue<bool> {
-        let initial_storage = env::storage_usage();
-
+    /**
+     * @notice Finalizes a `DataRequest` that never got a bonded outcome before its expiry time as `Invalid`
+     * @returns promise returning the validity bond and fee to the creator, if any
+     */
+    pub fn dr_expire(&mut self, request_id: U64) -> PromiseOrValue<bool> {
         let mut dr = self.dr_get_expect(request_id);
-        dr.assert_not_finalized();
-        dr.assert_final_arbitrator();
-        dr.assert_valid_outcome(&outcome);
-        dr.assert_final_arbitrator_invoked();
-        dr.finalize_final_arbitrator(outcome.clone());
+        dr.assert_can_expire();
+        dr.expire();
+        dr.deliver_outcome();
 
-        let config = self.configs.get(dr.global_config_id).unwrap();
-        dr.requester.set_outcome(outcome, dr.tags.clone(), true);
         self.data_requests.replace(request_id.into(), &dr);
 
         logger::log_update_data_request(&dr);
-        helpers::refund_storage(initial_storage, env::predecessor_account_id());
 
-        dr.return_validity_bond(config.payment_token)
+        // nobody staked so nobody can claim the fee, return it along with the validity bond
+        let refund = dr.request_config.validity_bond + dr.request_config.paid_fee;
+        if refund > 0 {
+            PromiseOrValue::Promise(fungible_token_transfer(
+                dr.payment_token,
+                dr.creator,
+                refund,
+            ))
+        } else {
+            PromiseOrValue::Value(false)
+        }
     }
 
-    fn dr_get_expect(&self, id: U64) -> DataRequest {
+    /**
+     * @notice Cancels a `DataRequest` nobody staked on yet and notifies the requester through `set_cancelled`
+     * @returns promise returning the validity bond and fee to the creator, if any
+     */
+    pub fn dr_cancel(&mut self, request_id: U64) -> PromiseOrValue<bool> {
+        let mut dr = self.dr_get_expect(request_id);
+        dr.assert_can_cancel();
+        dr.cancel();
+        dr.requester.set_cancelled(dr.tags.clone());
+
+        self.data_requests.replace(request_id.into(), &dr);
+
+        logger::log_update_data_request(&dr);
+
+        let refund = dr.request_config.validity_bond + dr.request_config.paid_fee;
+        if refund > 0 {
+            PromiseOrValue::Promise(fungible_token_transfer(
+                dr.payment_token,
+                dr.creator,
+                refund,
+            ))
+        } else {
+            PromiseOrValue::Value(false)
+        }
+    }
+
+    #[payable]
+    pub fn dr_final_arbitrator_finalize(
+        &mut self,
+        request_id: U64,
+        outcome: Outcome,
+    ) -> PromiseOrValue<bool> {
+        let initial_storage = env::storage_usage();
+
+        let mut dr = self.dr_get_expect(request_id);
+        dr.assert_final_arbitrator();
+        let validity_bond = self.final_arbitrator_finalize(&mut dr, outcome);
+
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+
+        validity_bond
+    }
+
+    /**
+     * @notice Finalizes a `DataRequest` the final arbitrator didn't finalize before its deadline according to the config's `final_arbitrator_fallback`
+     */
+    pub fn dr_final_arbitrator_fallback(&mut self, request_id: U64) -> PromiseOrValue<bool> {
+        let mut dr = self.dr_get_expect(request_id);
+        dr.assert_not_finalized();
+        dr.assert_final_arbitrator_invoked();
+        dr.assert_final_arbitrator_deadline_passed();
+
+        match dr.request_config.final_arbitrator_fallback {
+            oracle_config::FinalArbitratorFallback::LastBondedOutcome => {
+                // the round that invoked the final arbitrator is always the last, bonded, round
+                let outcome = dr
+                    .resolution_windows
+                    .get(dr.resolution_windows.len() - 1)
+                    .unwrap()
+                    .bonded_outcome
+                    .unwrap();
+                self.final_arbitrator_finalize(&mut dr, outcome)
+            }
+            oracle_config::FinalArbitratorFallback::Invalid => {
+                dr.refund_all_stakes = true;
+                dr.finalize_final_arbitrator(Outcome::Invalid);
+                dr.deliver_outcome();
+                self.data_requests.replace(request_id.into(), &dr);
+
+                logger::log_update_data_request(&dr);
+
+                dr.return_validity_bond()
+            }
+        }
+    }
+
+    /**
+     * @notice Handles the answer of a final arbitrator contract, `None` means it will call `dr_final_arbitrator_finalize` later
+     */
+    #[private]
+    pub fn dr_final_arbitrator_callback(&mut self, request_id: U64) -> PromiseOrValue<bool> {
+        assert_eq!(env::promise_results_count(), 1, "ERR_PROMISE_RESULTS_COUNT");
+        let outcome = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                serde_json::from_slice::<Option<Outcome>>(&value).unwrap_or(None)
+            }
+            _ => None,
+        };
+
+        let mut dr = self.dr_get_expect(request_id);
+        match outcome {
+            Some(outcome) if dr.finalized_outcome.is_none() => {
+                self.final_arbitrator_finalize(&mut dr, outcome)
+            }
+            _ => PromiseOrValue::Value(false),
+        }
+    }
+
+    fn final_arbitrator_finalize(
+        &mut self,
+        dr: &mut DataRequest,
+        outcome: Outcome,
+    ) -> PromiseOrValue<bool> {
+        let outcome = outcome.canonicalize();
+        dr.assert_not_finalized();
+        dr.assert_valid_outcome(&outcome);
+        dr.assert_final_arbitrator_invoked();
+        if !dr.is_bonded_outcome(&outcome) {
+            dr.finalize_unbonded_outcome();
+        }
+        dr.finalize_final_arbitrator(outcome);
+        dr.deliver_outcome();
+
+        let config = self.configs.get(dr.global_config_id).unwrap();
+        let (treasury_amount, burn_amount) = dr.calc_slashed_stake();
+        self.accrue_slashed_stake(dr.id, config.stake_token, treasury_amount, burn_amount);
+        self.data_requests.replace(dr.id, &dr);
+
+        logger::log_update_data_request(&dr);
+
+        dr.return_validity_bond()
+    }
+
+    /**
+     * @notice Records whether the requester accepted the finalized outcome
+     */
+    #[private]
+    pub fn dr_proceed_finalization(&mut self, request_id: U64) {
+        assert_eq!(env::promise_results_count(), 1, "ERR_PROMISE_RESULTS_COUNT");
+        let delivered = match env::promise_result(0) {
+            PromiseResult::Successful(_) => true,
+            _ => false,
+        };
+
+        let mut dr = self.dr_get_expect(request_id);
+        dr.delivery_status = Some(if delivered {
+            DeliveryStatus::Delivered
+        } else {
+            DeliveryStatus::Failed
+        });
+        self.data_requests.replace(request_id.into(), &dr);
+
+        logger::log_outcome_delivery(dr.id, dr.delivery_status.as_ref().unwrap());
+    }
+
+    /**
+     * @notice Re-sends the finalized outcome to a requester that failed to process it, or whose delivery is still pending after
+     * `DELIVERY_RETRY_TIMEOUT` because its callback ran out of gas
+     */
+    pub fn dr_retry_delivery(&mut self, request_id: U64) -> Promise {
+        let mut dr = self.dr_get_expect(request_id);
+        dr.assert_finalized();
+        let can_retry = match dr.delivery_status {
+            Some(DeliveryStatus::Failed) => true,
+            Some(DeliveryStatus::Pending) => {
+                env::block_timestamp() >= dr.delivery_sent_at.unwrap() + DELIVERY_RETRY_TIMEOUT
+            }
+            _ => false,
+        };
+        assert!(
+            can_retry,
+            "Outcome delivery can only be retried after it failed or stayed pending for {}",
+            DELIVERY_RETRY_TIMEOUT
+        );
+
+        let promise = dr.deliver_outcome();
+        self.data_requests.replace(request_id.into(), &dr);
+
+        logger::log_outcome_delivery(dr.id, dr.delivery_status.as_ref().unwrap());
+        promise
+    }
+
+    // @returns the id of the newly stored `DataRequest`
+    pub(crate) fn dr_insert(
+        &mut self,
+        requester: Requester,
+        config: &oracle_config::OracleConfig,
+        payment_token: AccountId,
+        validity_bond: Balance,
+        paid_fee: Balance,
+        payload: NewDataRequestArgs,
+        subscription_id: Option<u64>,
+    ) -> u64 {
+        // the fee denominated in the config's `payment_token` rather than the token it was paid in
+        let resolution_fee = config.fee.calc_resolution_fee(
+            self.total_value_secured,
+            requester.get_total_value_secured(),
+        );
+        let mut dr = DataRequest::new(
+            requester,
+            self.data_requests.len() as u64, // dr_id
+            self.configs.len() - 1,          // dr's config id
+            config,
+            payment_token,
+            validity_bond,
+            paid_fee,
+            resolution_fee,
+            payload,
+        );
+        dr.subscription_id = subscription_id;
+
+        logger::log_new_data_request(&dr);
+
+        self.data_requests.push(&dr);
+        dr.id
+    }
+
+    fn dr_get_expect(&self, id: U64) -> DataRequest {
         self.data_requests
             .get(id.into())
             .expect("ERR_DATA_REQUEST_NOT_FOUND")
@@ -710,6 +1758,60 @@ impl Contract {
             .map(|index| self.data_requests.get(index).unwrap().summarize_dr())
             .collect()
     }
+
+    /**
+     * @notice Expected payout of `dr_claim`, for requests that aren't finalized yet the currently bonded outcome is assumed final
+     */
+    pub fn dr_claimable(&self, account_id: AccountId, request_id: U64) -> ClaimableSummary {
+        let dr = self.dr_get_expect(request_id);
+        let config = self.configs.get(dr.global_config_id).unwrap();
+        let claimable = dr.claimable(&account_id);
+
+        ClaimableSummary {
+            stake_token: config.stake_token,
+            stake_token_payout: U128(claimable.stake_token_payout),
+            payment_token: dr.payment_token,
+            payment_token_payout: U128(claimable.payment_token_payout),
+        }
+    }
+
+    /**
+     * @returns every round and outcome `account_id` has stake on within the `limit` requests starting at `from_index`
+     */
+    pub fn get_account_stakes(
+        &self,
+        account_id: AccountId,
+        from_index: U64,
+        limit: U64,
+    ) -> Vec<AccountStakeSummary> {
+        let i: u64 = from_index.into();
+        (i..std::cmp::min(i.saturating_add(u64::from(limit)), self.data_requests.len()))
+            .flat_map(|index| {
+                let dr = self.data_requests.get(index).unwrap();
+                dr.resolution_windows
+                    .iter()
+                    .filter_map(|window| {
+                        window
+                            .user_to_outcome_to_stake
+                            .get(&account_id)
+                            .map(|outcome_to_stake| (window.round, outcome_to_stake))
+                    })
+                    .flat_map(|(round, outcome_to_stake)| {
+                        outcome_to_stake
+                            .iter()
+                            .filter(|(_, stake)| *stake > 0)
+                            .map(|(outcome, stake)| AccountStakeSummary {
+                                request_id: U64(index),
+                                round,
+                                outcome,
+                                stake: U128(stake),
+                            })
+                            .collect::<Vec<AccountStakeSummary>>()
+                    })
+                    .collect::<Vec<AccountStakeSummary>>()
+            })
+            .collect()
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -718,7 +1820,7 @@ mod mock_token_basic_tests {
     use super::*;
     use crate::data_request::AnswerType;
     use crate::requester_handler::Requester;
-    use fee_config::FeeConfig;
+    use crate::test_utils::config;
     use near_sdk::MockedBlockchain;
     use near_sdk::{testing_env, VMContext};
 
@@ -742,8 +1844,8 @@ mod mock_token_basic_tests {
         "token.near".to_string()
     }
 
-    fn gov() -> AccountId {
-        "gov.near".to_string()
+    fn usdc() -> AccountId {
+        "usdc.near".to_string()
     }
 
     fn sum_claim_res(claim_res: ClaimRes) -> u128 {
@@ -756,6 +1858,7 @@ mod mock_token_basic_tests {
             account_id: account.clone(),
             stake_multiplier: None,
             code_base_url: None,
+            total_value_secured: None,
         }
     }
 
@@ -766,25 +1869,6 @@ mod mock_token_basic_tests {
         contract
     }
 
-    fn config() -> oracle_config::OracleConfig {
-        oracle_config::OracleConfig {
-            gov: gov(),
-            final_arbitrator: alice(),
-            payment_token: token(),
-            stake_token: token(),
-            validity_bond: U128(100),
-            max_outcomes: 8,
-            default_challenge_window_duration: U64(1000),
-            min_initial_challenge_window_duration: U64(1000),
-            final_arbitrator_invoke_amount: U128(250),
-            fee: FeeConfig {
-                flux_market_cap: U128(50000),
-                total_value_staked: U128(10000),
-                resolution_fee_percentage: 10_000,
-            },
-        }
-    }
-
     fn get_context(predecessor_account_id: AccountId) -> VMContext {
         VMContext {
             current_account_id: token(),
@@ -823,6 +1907,8 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
@@ -844,13 +1930,15 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
     }
 
     #[test]
-    #[should_panic(expected = "This function can only be called by token.near")]
+    #[should_panic(expected = "Unsupported payment token alice.near")]
     fn dr_new_non_payment_token() {
         testing_env!(get_context(alice()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
@@ -865,9 +1953,51 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
+                creator: bob(),
+            },
+        );
+    }
+
+    #[test]
+    fn dr_new_approved_payment_token() {
+        testing_env!(get_context(usdc()));
+        let mut bob_requester = registry_entry(bob());
+        bob_requester.total_value_secured = Some(U128(200));
+        let whitelist = Some(vec![bob_requester, registry_entry(carol())]);
+        let mut config = config();
+        config.payment_tokens = vec![oracle_config::PaymentToken {
+            token: usdc(),
+            validity_bond: U128(50),
+            fee_multiplier: U128(3),
+            fee_divisor: U128(2),
+        }];
+        let mut contract = Contract::new(whitelist, config);
+        let unspent = contract.dr_new(
+            bob(),
+            100,
+            NewDataRequestArgs {
+                sources: Vec::new(),
+                outcomes: Some(vec!["a".to_string(), "b".to_string()].to_vec()),
+                challenge_period: U64(1500),
+                description: Some("a".to_string()),
+                tags: vec!["1".to_string()],
+                data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
+
+        // validity bond of 50 usdc and 10% of the 200 secured by bob converted at 3/2
+        assert_eq!(unspent, 20);
+        let request: DataRequest = contract.data_requests.get(0).unwrap();
+        assert_eq!(request.payment_token, usdc());
+        assert_eq!(request.request_config.validity_bond, 50);
+        assert_eq!(request.request_config.paid_fee, 30);
+        // the resolution bond is based on the validity bond of 100 token rather than the 50 usdc paid
+        assert_eq!(request.calc_resolution_bond(), 200);
     }
 
     #[test]
@@ -922,6 +2052,8 @@ mod mock_token_basic_tests {
                 description: None,
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
@@ -954,6 +2086,8 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
@@ -975,6 +2109,8 @@ mod mock_token_basic_tests {
                 description: None,
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
@@ -997,6 +2133,8 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
@@ -1019,6 +2157,8 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
@@ -1041,6 +2181,8 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
@@ -1062,12 +2204,76 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
         assert_eq!(amount, 0);
     }
 
+    #[test]
+    #[should_panic(expected = "Resolution fee of 20 not reached, received only 10")]
+    fn dr_new_fee_not_reached() {
+        testing_env!(get_context(token()));
+        let mut bob_requester = registry_entry(bob());
+        bob_requester.total_value_secured = Some(U128(200));
+        let whitelist = Some(vec![bob_requester, registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+
+        contract.dr_new(
+            bob(),
+            110,
+            NewDataRequestArgs {
+                sources: Vec::new(),
+                outcomes: None,
+                challenge_period: U64(1500),
+                description: Some("a".to_string()),
+                tags: vec!["1".to_string()],
+                data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
+                creator: bob(),
+            },
+        );
+    }
+
+    #[test]
+    fn dr_new_refund_overpaid_fee() {
+        testing_env!(get_context(token()));
+        let mut bob_requester = registry_entry(bob());
+        bob_requester.total_value_secured = Some(U128(200));
+        let whitelist = Some(vec![bob_requester, registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+
+        let unspent: Balance = contract.dr_new(
+            bob(),
+            150,
+            NewDataRequestArgs {
+                sources: Vec::new(),
+                outcomes: None,
+                challenge_period: U64(1500),
+                description: Some("a".to_string()),
+                tags: vec!["1".to_string()],
+                data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
+                creator: bob(),
+            },
+        );
+        // validity bond of 100 and 10% of the 200 secured by bob
+        assert_eq!(unspent, 30);
+        assert_eq!(
+            contract
+                .data_requests
+                .get(0)
+                .unwrap()
+                .request_config
+                .paid_fee,
+            20
+        );
+    }
+
     fn dr_new(contract: &mut Contract) {
         contract.dr_new(
             bob(),
@@ -1079,6 +2285,8 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
@@ -1186,6 +2394,8 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
@@ -1378,8 +2588,7 @@ mod mock_token_basic_tests {
     }
 
     #[test]
-    #[should_panic(expected = "Outcome is incompatible for this round")]
-    fn dr_stake_same_outcome() {
+    fn dr_finalize_delivery_pending() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let mut contract = Contract::new(whitelist, config());
@@ -1387,65 +2596,175 @@ mod mock_token_basic_tests {
 
         contract.dr_stake(
             alice(),
-            300,
+            200,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
+        assert_eq!(contract.data_requests.get(0).unwrap().delivery_status, None);
+
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1501;
+        testing_env!(ct);
+        contract.dr_finalize(U64(0));
+
+        assert_eq!(
+            contract.data_requests.get(0).unwrap().delivery_status,
+            Some(DeliveryStatus::Pending)
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Outcome delivery can only be retried after it failed or stayed pending for 3600000000000"
+    )]
+    fn dr_retry_delivery_pending() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
 
         contract.dr_stake(
             alice(),
-            500,
+            200,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
+
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1501;
+        testing_env!(ct);
+        contract.dr_finalize(U64(0));
+        contract.dr_retry_delivery(U64(0));
     }
 
-    fn dr_finalize(contract: &mut Contract, outcome: Outcome) {
+    #[test]
+    fn dr_retry_delivery_pending_timeout() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+
         contract.dr_stake(
             alice(),
-            2000,
+            200,
             StakeDataRequestArgs {
                 id: U64(0),
-                outcome: outcome,
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
 
         let mut ct: VMContext = get_context(token());
         ct.block_timestamp = 1501;
         testing_env!(ct);
+        contract.dr_finalize(U64(0));
 
-        finalize(contract, 0);
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1501 + DELIVERY_RETRY_TIMEOUT;
+        testing_env!(ct);
+        contract.dr_retry_delivery(U64(0));
+
+        let dr = contract.data_requests.get(0).unwrap();
+        assert_eq!(dr.delivery_status, Some(DeliveryStatus::Pending));
+        assert_eq!(dr.delivery_sent_at, Some(1501 + DELIVERY_RETRY_TIMEOUT));
     }
 
     #[test]
-    #[should_panic(expected = "ERR_DATA_REQUEST_NOT_FOUND")]
-    fn dr_unstake_invalid_id() {
+    fn dr_retry_delivery_failed() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let mut contract = Contract::new(whitelist, config());
-
-        contract.dr_unstake(
-            U64(0),
-            0,
+        dr_new(&mut contract);
+        dr_finalize(
+            &mut contract,
             data_request::Outcome::Answer(AnswerType::String("a".to_string())),
-            U128(0),
+        );
+
+        let mut dr = contract.data_requests.get(0).unwrap();
+        dr.delivery_status = Some(DeliveryStatus::Failed);
+        contract.data_requests.replace(0, &dr);
+
+        contract.dr_retry_delivery(U64(0));
+        assert_eq!(
+            contract.data_requests.get(0).unwrap().delivery_status,
+            Some(DeliveryStatus::Pending)
         );
     }
 
     #[test]
-    #[should_panic(expected = "Cannot withdraw from bonded outcome")]
-    fn dr_unstake_bonded_outcome() {
+    #[should_panic(expected = "Outcome is incompatible for this round")]
+    fn dr_stake_same_outcome() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let mut contract = Contract::new(whitelist, config());
         dr_new(&mut contract);
-        dr_finalize(
-            &mut contract,
-            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+
+        contract.dr_stake(
+            alice(),
+            300,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+
+        contract.dr_stake(
+            alice(),
+            500,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+    }
+
+    fn dr_finalize(contract: &mut Contract, outcome: Outcome) {
+        contract.dr_stake(
+            alice(),
+            2000,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: outcome,
+            },
+        );
+
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1501;
+        testing_env!(ct);
+
+        finalize(contract, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_DATA_REQUEST_NOT_FOUND")]
+    fn dr_unstake_invalid_id() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+
+        contract.dr_unstake(
+            U64(0),
+            0,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            U128(0),
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Can only unstake from the current or a non-bonded resolution window"
+    )]
+    fn dr_unstake_previous_window() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+        dr_finalize(
+            &mut contract,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
         );
 
         contract.dr_unstake(
@@ -1470,12 +2789,41 @@ mod mock_token_basic_tests {
 
         contract.dr_unstake(
             U64(0),
-            0,
+            1,
             data_request::Outcome::Answer(AnswerType::String("c".to_string())),
             U128(1),
         );
     }
 
+    #[test]
+    #[should_panic(expected = "Cannot withdraw from bonded outcome")]
+    fn dr_unstake_bonded_outcome() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut c: oracle_config::OracleConfig = config();
+        c.final_arbitrator_invoke_amount = U128(150);
+        let mut contract = Contract::new(whitelist, c);
+        dr_new(&mut contract);
+
+        // bonds the current window and triggers the final arbitrator
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+
+        testing_env!(get_context(alice()));
+        contract.dr_unstake(
+            U64(0),
+            0,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            U128(1),
+        );
+    }
+
     #[test]
     #[should_panic(
         expected = "alice.near has less staked on this outcome (10) than unstake amount"
@@ -1592,31 +2940,65 @@ mod mock_token_basic_tests {
     }
 
     #[test]
-    #[should_panic(expected = "ERR_DATA_REQUEST_NOT_FOUND")]
-    fn dr_claim_invalid_id() {
+    fn dr_unstake_then_claim() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
 
-        contract.dr_claim(alice(), U64(0));
+        let outcome = data_request::Outcome::Answer(AnswerType::String("b".to_string()));
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+        contract.dr_stake(
+            carol(),
+            100,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: outcome.clone(),
+            },
+        );
+
+        testing_env!(get_context(carol()));
+        contract.dr_unstake(U64(0), 1, outcome.clone(), U128(100));
+
+        let round1 = contract
+            .data_requests
+            .get(0)
+            .unwrap()
+            .resolution_windows
+            .get(1)
+            .unwrap();
+        assert_eq!(round1.outcome_to_stake.get(&outcome).unwrap(), 0);
+        assert!(round1.bonded_outcome.is_none());
+
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1501;
+        testing_env!(ct);
+        finalize(&mut contract, 0);
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        assert_eq!(sum_claim_res(d.claim(alice())), 200);
+        assert_eq!(sum_claim_res(d.claim(carol())), 0);
     }
 
     #[test]
-    fn dr_claim_success() {
+    #[should_panic(expected = "ERR_DATA_REQUEST_NOT_FOUND")]
+    fn dr_claim_invalid_id() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let mut contract = Contract::new(whitelist, config());
-        dr_new(&mut contract);
-        dr_finalize(
-            &mut contract,
-            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
-        );
 
         contract.dr_claim(alice(), U64(0));
     }
 
     #[test]
-    fn d_claim_single() {
+    fn dr_claim_success() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let mut contract = Contract::new(whitelist, config());
@@ -1626,111 +3008,133 @@ mod mock_token_basic_tests {
             data_request::Outcome::Answer(AnswerType::String("a".to_string())),
         );
 
-        let mut d = contract.data_requests.get(0).unwrap();
-        // validity bond
-        assert_eq!(sum_claim_res(d.claim(alice())), 200);
+        contract.dr_claim(alice(), U64(0));
     }
 
     #[test]
-    fn d_claim_same_twice() {
+    fn dr_claim_many_success() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let mut contract = Contract::new(whitelist, config());
         dr_new(&mut contract);
-        dr_finalize(
-            &mut contract,
-            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
-        );
+        dr_new(&mut contract);
 
-        let mut d = contract.data_requests.get(0).unwrap();
-        // validity bond
-        assert_eq!(sum_claim_res(d.claim(alice())), 200);
-        assert_eq!(sum_claim_res(d.claim(alice())), 0);
+        for id in 0..2 {
+            contract.dr_stake(
+                alice(),
+                200,
+                StakeDataRequestArgs {
+                    id: U64(id),
+                    outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+                },
+            );
+        }
+
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1501;
+        testing_env!(ct);
+        contract.dr_finalize(U64(0));
+        contract.dr_finalize(U64(1));
+
+        contract.dr_claim_many(alice(), vec![U64(0), U64(1)]);
+
+        for id in 0..2 {
+            let d = contract.data_requests.get(id).unwrap();
+            assert!(d.claimed.contains(&alice()));
+        }
     }
 
     #[test]
-    fn d_validity_bond() {
+    #[should_panic(expected = "can't claim 0")]
+    fn dr_claim_many_nothing_staked() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
-        let mut config = config();
-        config.validity_bond = U128(2);
-        let mut contract = Contract::new(whitelist, config);
+        let mut contract = Contract::new(whitelist, config());
         dr_new(&mut contract);
         dr_finalize(
             &mut contract,
             data_request::Outcome::Answer(AnswerType::String("a".to_string())),
         );
 
-        let mut d = contract.data_requests.get(0).unwrap();
-        // fees (100% of TVL)
-        assert_eq!(sum_claim_res(d.claim(alice())), 294);
+        contract.dr_claim_many(carol(), vec![U64(0)]);
     }
 
     #[test]
-    fn d_claim_double() {
+    fn dr_claimable_success() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let mut contract = Contract::new(whitelist, config());
         dr_new(&mut contract);
 
         contract.dr_stake(
-            bob(),
-            100,
+            alice(),
+            200,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
-        dr_finalize(
-            &mut contract,
-            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
-        );
-
-        let mut d = contract.data_requests.get(0).unwrap();
-        // validity bond
-        assert_eq!(sum_claim_res(d.claim(alice())), 100);
-        assert_eq!(sum_claim_res(d.claim(bob())), 100);
-    }
-
-    #[test]
-    fn d_claim_2rounds_single() {
-        testing_env!(get_context(token()));
-        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
-        let mut config = config();
-        config.final_arbitrator_invoke_amount = U128(1000);
-        let mut contract = Contract::new(whitelist, config);
-        dr_new(&mut contract);
-
         contract.dr_stake(
-            bob(),
-            200,
+            carol(),
+            100,
             StakeDataRequestArgs {
                 id: U64(0),
-                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
             },
         );
-        dr_finalize(
-            &mut contract,
-            data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+
+        // not finalized yet, assumes the bonded outcome is final
+        let claimable = contract.dr_claimable(alice(), U64(0));
+        assert_eq!(claimable.stake_token, token());
+        assert_eq!(claimable.stake_token_payout, U128(200));
+        assert_eq!(claimable.payment_token_payout, U128(0));
+
+        let stakes = contract.get_account_stakes(carol(), U64(0), U64(10));
+        assert_eq!(stakes.len(), 1);
+        assert_eq!(stakes[0].request_id, U64(0));
+        assert_eq!(stakes[0].round, 1);
+        assert_eq!(
+            stakes[0].outcome,
+            data_request::Outcome::Answer(AnswerType::String("b".to_string()))
         );
+        assert_eq!(stakes[0].stake, U128(100));
+
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1501;
+        testing_env!(ct);
+        finalize(&mut contract, 0);
 
+        // viewing doesn't remove stake
+        assert_eq!(
+            contract.dr_claimable(alice(), U64(0)).stake_token_payout,
+            U128(200)
+        );
         let mut d = contract.data_requests.get(0).unwrap();
-        // validity bond + round 0 stake
-        assert_eq!(sum_claim_res(d.claim(alice())), 600);
-        assert_eq!(sum_claim_res(d.claim(bob())), 0);
+        assert_eq!(sum_claim_res(d.claim(alice())), 200);
+        assert_eq!(
+            contract.dr_claimable(alice(), U64(0)).stake_token_payout,
+            U128(0)
+        );
+        // carol's stake in the unbonded last round is refunded
+        assert_eq!(
+            contract.dr_claimable(carol(), U64(0)).stake_token_payout,
+            U128(100)
+        );
+        assert_eq!(
+            contract.get_account_stakes(alice(), U64(0), U64(10)).len(),
+            0
+        );
     }
 
     #[test]
-    fn d_claim_2rounds_double() {
+    fn dr_claim_bonded_and_unbonded_stake() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
-        let mut config = config();
-        config.final_arbitrator_invoke_amount = U128(1000);
-        let mut contract = Contract::new(whitelist, config);
+        let mut contract = Contract::new(whitelist, config());
         dr_new(&mut contract);
 
         contract.dr_stake(
-            bob(),
+            alice(),
             200,
             StakeDataRequestArgs {
                 id: U64(0),
@@ -1738,36 +3142,82 @@ mod mock_token_basic_tests {
             },
         );
         contract.dr_stake(
-            carol(),
+            alice(),
             100,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
             },
         );
-        dr_finalize(
-            &mut contract,
-            data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+        assert_eq!(
+            contract.get_account_stakes(alice(), U64(0), U64(10)).len(),
+            2
         );
 
-        let mut d = contract.data_requests.get(0).unwrap();
-        // validity bond + round 0 stake
-        assert_eq!(sum_claim_res(d.claim(alice())), 450);
-        assert_eq!(sum_claim_res(d.claim(bob())), 0);
-        assert_eq!(sum_claim_res(d.claim(carol())), 150);
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1501;
+        testing_env!(ct);
+        contract.dr_finalize(U64(0));
+
+        assert_eq!(
+            contract.dr_claimable(alice(), U64(0)).stake_token_payout,
+            U128(300)
+        );
+        contract.dr_claim(alice(), U64(0));
+
+        assert_eq!(
+            contract.get_account_stakes(alice(), U64(0), U64(10)).len(),
+            0
+        );
+        assert_eq!(
+            contract.dr_claimable(alice(), U64(0)).stake_token_payout,
+            U128(0)
+        );
     }
 
     #[test]
-    fn d_claim_3rounds_single() {
+    fn d_claim_single() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
-        let mut config = config();
-        config.final_arbitrator_invoke_amount = U128(1000);
-        let mut contract = Contract::new(whitelist, config);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+        dr_finalize(
+            &mut contract,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+        );
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        // validity bond
+        assert_eq!(sum_claim_res(d.claim(alice())), 200);
+    }
+
+    #[test]
+    #[should_panic(expected = "alice.near already claimed DataRequest 0")]
+    fn d_claim_same_twice() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+        dr_finalize(
+            &mut contract,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+        );
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        // validity bond
+        assert_eq!(sum_claim_res(d.claim(alice())), 200);
+        d.claim(alice());
+    }
+
+    #[test]
+    fn d_claim_refund_unbonded_last_round() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
         dr_new(&mut contract);
 
         contract.dr_stake(
-            bob(),
+            alice(),
             200,
             StakeDataRequestArgs {
                 id: U64(0),
@@ -1775,33 +3225,68 @@ mod mock_token_basic_tests {
             },
         );
         contract.dr_stake(
-            carol(),
-            400,
+            bob(),
+            100,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
             },
         );
+        contract.dr_stake(
+            carol(),
+            50,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Invalid,
+            },
+        );
+
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1501;
+        testing_env!(ct);
+        finalize(&mut contract, 0);
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        assert_eq!(sum_claim_res(d.claim(alice())), 200);
+        // round 1 never filled so its stakes are returned
+        assert_eq!(sum_claim_res(d.claim(bob())), 100);
+        assert_eq!(sum_claim_res(d.claim(carol())), 50);
+        assert!(d.claimed.contains(&bob()));
+        assert!(d
+            .resolution_windows
+            .get(1)
+            .unwrap()
+            .user_to_outcome_to_stake
+            .get(&bob())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn d_validity_bond() {
+        testing_env!(get_context(token()));
+        let mut bob_requester = registry_entry(bob());
+        bob_requester.total_value_secured = Some(U128(980));
+        let whitelist = Some(vec![bob_requester, registry_entry(carol())]);
+        let mut config = config();
+        config.validity_bond = U128(2);
+        let mut contract = Contract::new(whitelist, config);
+        dr_new(&mut contract);
         dr_finalize(
             &mut contract,
             data_request::Outcome::Answer(AnswerType::String("a".to_string())),
         );
 
         let mut d = contract.data_requests.get(0).unwrap();
-        // round 1 stake
-        assert_eq!(sum_claim_res(d.claim(alice())), 1120);
-        // validity bond
-        assert_eq!(sum_claim_res(d.claim(bob())), 280);
-        assert_eq!(sum_claim_res(d.claim(carol())), 0);
+        // fees (100% of TVL)
+        assert_eq!(sum_claim_res(d.claim(alice())), 294);
     }
 
     #[test]
-    fn d_claim_3rounds_double_round0() {
+    fn d_claim_double() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
-        let mut config = config();
-        config.final_arbitrator_invoke_amount = U128(1000);
-        let mut contract = Contract::new(whitelist, config);
+        let mut contract = Contract::new(whitelist, config());
         dr_new(&mut contract);
 
         contract.dr_stake(
@@ -1812,39 +3297,47 @@ mod mock_token_basic_tests {
                 outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
-        contract.dr_stake(
-            dave(),
-            100,
-            StakeDataRequestArgs {
-                id: U64(0),
-                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
-            },
+        dr_finalize(
+            &mut contract,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
         );
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        // validity bond
+        assert_eq!(sum_claim_res(d.claim(alice())), 100);
+        assert_eq!(sum_claim_res(d.claim(bob())), 100);
+    }
+
+    #[test]
+    fn d_claim_2rounds_single() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut config = config();
+        config.final_arbitrator_invoke_amount = U128(1000);
+        let mut contract = Contract::new(whitelist, config);
+        dr_new(&mut contract);
+
         contract.dr_stake(
-            carol(),
-            400,
+            bob(),
+            200,
             StakeDataRequestArgs {
                 id: U64(0),
-                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
         dr_finalize(
             &mut contract,
-            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            data_request::Outcome::Answer(AnswerType::String("b".to_string())),
         );
 
         let mut d = contract.data_requests.get(0).unwrap();
-        // round 1 stake
-        assert_eq!(sum_claim_res(d.claim(alice())), 1120);
-        // 50% of validity bond
-        assert_eq!(sum_claim_res(d.claim(bob())), 140);
-        assert_eq!(sum_claim_res(d.claim(carol())), 0);
-        // 50% of validity bond
-        assert_eq!(sum_claim_res(d.claim(dave())), 140);
+        // validity bond + round 0 stake
+        assert_eq!(sum_claim_res(d.claim(alice())), 600);
+        assert_eq!(sum_claim_res(d.claim(bob())), 0);
     }
 
     #[test]
-    fn d_claim_3rounds_double_round2() {
+    fn d_claim_2rounds_double() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let mut config = config();
@@ -1862,134 +3355,123 @@ mod mock_token_basic_tests {
         );
         contract.dr_stake(
             carol(),
-            400,
+            100,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
             },
         );
-        contract.dr_stake(
-            dave(),
-            300,
-            StakeDataRequestArgs {
-                id: U64(0),
-                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
-            },
-        );
         dr_finalize(
             &mut contract,
-            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            data_request::Outcome::Answer(AnswerType::String("b".to_string())),
         );
 
         let mut d = contract.data_requests.get(0).unwrap();
-        // 5/8 of round 1 stake
-        assert_eq!(sum_claim_res(d.claim(alice())), 700);
-        // validity bond
-        assert_eq!(sum_claim_res(d.claim(bob())), 280);
-        assert_eq!(sum_claim_res(d.claim(carol())), 0);
-        // 3/8 of round 1 stake
-        assert_eq!(sum_claim_res(d.claim(dave())), 420);
+        // validity bond + round 0 stake
+        assert_eq!(sum_claim_res(d.claim(alice())), 450);
+        assert_eq!(sum_claim_res(d.claim(bob())), 0);
+        assert_eq!(sum_claim_res(d.claim(carol())), 150);
     }
 
     #[test]
-    fn d_claim_final_arb() {
+    fn d_claim_3rounds_single() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
-        let mut contract = Contract::new(whitelist, config());
-        // needed for final arb function
+        let mut config = config();
+        config.final_arbitrator_invoke_amount = U128(1000);
+        let mut contract = Contract::new(whitelist, config);
         dr_new(&mut contract);
 
         contract.dr_stake(
-            alice(),
+            bob(),
             200,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
-        // This round exceeds final arb limit, will be used as signal
         contract.dr_stake(
-            bob(),
+            carol(),
             400,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
             },
         );
-
-        testing_env!(get_context(alice()));
-        contract.dr_final_arbitrator_finalize(
-            U64(0),
+        dr_finalize(
+            &mut contract,
             data_request::Outcome::Answer(AnswerType::String("a".to_string())),
         );
 
         let mut d = contract.data_requests.get(0).unwrap();
-        assert_eq!(sum_claim_res(d.claim(alice())), 600);
-        assert_eq!(sum_claim_res(d.claim(bob())), 0);
+        // round 1 stake
+        assert_eq!(sum_claim_res(d.claim(alice())), 1120);
+        // validity bond
+        assert_eq!(sum_claim_res(d.claim(bob())), 280);
+        assert_eq!(sum_claim_res(d.claim(carol())), 0);
     }
 
     #[test]
-    fn d_claim_final_arb_extra_round() {
+    fn d_claim_3rounds_double_round0() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let mut config = config();
-        config.final_arbitrator_invoke_amount = U128(600);
+        config.final_arbitrator_invoke_amount = U128(1000);
         let mut contract = Contract::new(whitelist, config);
-        // needed for final arb function
         dr_new(&mut contract);
 
         contract.dr_stake(
-            alice(),
-            200,
+            bob(),
+            100,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
         contract.dr_stake(
-            bob(),
-            400,
+            dave(),
+            100,
             StakeDataRequestArgs {
                 id: U64(0),
-                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
-        // This round exceeds final arb limit, will be used as signal
         contract.dr_stake(
             carol(),
-            800,
+            400,
             StakeDataRequestArgs {
                 id: U64(0),
-                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
             },
         );
-
-        testing_env!(get_context(alice()));
-        contract.dr_final_arbitrator_finalize(
-            U64(0),
+        dr_finalize(
+            &mut contract,
             data_request::Outcome::Answer(AnswerType::String("a".to_string())),
         );
 
         let mut d = contract.data_requests.get(0).unwrap();
-        // validity bond
-        assert_eq!(sum_claim_res(d.claim(alice())), 280);
-        assert_eq!(sum_claim_res(d.claim(bob())), 0);
-        // round 1 funds
-        assert_eq!(sum_claim_res(d.claim(carol())), 1120);
+        // round 1 stake
+        assert_eq!(sum_claim_res(d.claim(alice())), 1120);
+        // 50% of validity bond
+        assert_eq!(sum_claim_res(d.claim(bob())), 140);
+        assert_eq!(sum_claim_res(d.claim(carol())), 0);
+        // 50% of validity bond
+        assert_eq!(sum_claim_res(d.claim(dave())), 140);
     }
 
     #[test]
-    fn d_claim_final_arb_extra_round2() {
+    fn d_claim_treasury_share() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let mut config = config();
-        config.final_arbitrator_invoke_amount = U128(600);
+        config.final_arbitrator_invoke_amount = U128(1000);
+        config.treasury_share = 1000;
+        config.burn_share = 500;
         let mut contract = Contract::new(whitelist, config);
-        // needed for final arb function
         dr_new(&mut contract);
 
         contract.dr_stake(
-            alice(),
+            bob(),
             200,
             StakeDataRequestArgs {
                 id: U64(0),
@@ -1997,56 +3479,49 @@ mod mock_token_basic_tests {
             },
         );
         contract.dr_stake(
-            bob(),
+            carol(),
             400,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
             },
         );
-        // This round exceeds final arb limit, will be used as signal
         contract.dr_stake(
-            carol(),
-            800,
+            alice(),
+            2000,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
 
-        testing_env!(get_context(alice()));
-        contract.dr_final_arbitrator_finalize(
-            U64(0),
-            data_request::Outcome::Answer(AnswerType::String("b".to_string())),
-        );
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1501;
+        testing_env!(ct);
+        contract.dr_finalize(U64(0));
+
+        // 10% of round 1 stake, another 5% is burned
+        assert_eq!(contract.get_treasury_balance(token()), U128(40));
+        assert_eq!(contract.get_burned_balance(token()), U128(20));
 
         let mut d = contract.data_requests.get(0).unwrap();
-        assert_eq!(sum_claim_res(d.claim(alice())), 0);
-        // validity bond (100), round0 (200), round2 (800)
-        assert_eq!(sum_claim_res(d.claim(bob())), 1400);
+        // round 2 stake + 4/5 of the remaining 340
+        assert_eq!(sum_claim_res(d.claim(alice())), 1072);
+        // round 0 stake + 1/5 of the remaining 340
+        assert_eq!(sum_claim_res(d.claim(bob())), 268);
         assert_eq!(sum_claim_res(d.claim(carol())), 0);
     }
 
     #[test]
-    #[should_panic(expected = "Final arbitrator is invoked for `DataRequest` with id: 0")]
-    fn dr_final_arb_invoked() {
+    fn d_claim_refund_unbonded_outcome() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
-        let config = config();
-        let mut contract = Contract::new(whitelist, config);
+        let mut contract = Contract::new(whitelist, config());
         dr_new(&mut contract);
 
-        contract.dr_stake(
-            alice(),
-            200,
-            StakeDataRequestArgs {
-                id: U64(0),
-                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
-            },
-        );
         contract.dr_stake(
             bob(),
-            400,
+            100,
             StakeDataRequestArgs {
                 id: U64(0),
                 outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
@@ -2054,21 +3529,127 @@ mod mock_token_basic_tests {
         );
         contract.dr_stake(
             carol(),
-            400,
+            150,
             StakeDataRequestArgs {
                 id: U64(0),
-                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+                outcome: data_request::Outcome::Invalid,
             },
         );
-    }
-
+        dr_finalize(
+            &mut contract,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+        );
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        assert_eq!(
+            contract.dr_claimable(bob(), U64(0)).stake_token_payout,
+            U128(100)
+        );
+        assert_eq!(sum_claim_res(d.claim(alice())), 200);
+        // outcomes that didn't fill round 0 are returned
+        assert_eq!(sum_claim_res(d.claim(bob())), 100);
+        assert_eq!(sum_claim_res(d.claim(carol())), 150);
+    }
+
     #[test]
-    #[should_panic(expected = "Incompatible outcome")]
-    fn dr_final_arb_invalid_outcome() {
+    fn d_claim_refund_unbonded_outcome_later_round() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
-        let config = config();
+        let mut config = config();
+        config.final_arbitrator_invoke_amount = U128(1000);
+        let mut contract = Contract::new(whitelist, config);
+        dr_new(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+        contract.dr_stake(
+            dave(),
+            100,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Invalid,
+            },
+        );
+        contract.dr_stake(
+            bob(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+            },
+        );
+        dr_finalize(
+            &mut contract,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+        );
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        // round 0 and 2 stake + round 1 stake
+        assert_eq!(sum_claim_res(d.claim(alice())), 1400);
+        assert_eq!(sum_claim_res(d.claim(bob())), 0);
+        // dave's stake on `Invalid` in round 1 never got bonded
+        assert_eq!(sum_claim_res(d.claim(dave())), 100);
+    }
+
+    #[test]
+    fn d_claim_3rounds_double_round2() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut config = config();
+        config.final_arbitrator_invoke_amount = U128(1000);
         let mut contract = Contract::new(whitelist, config);
+        dr_new(&mut contract);
+
+        contract.dr_stake(
+            bob(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+        contract.dr_stake(
+            carol(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+            },
+        );
+        contract.dr_stake(
+            dave(),
+            300,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+        dr_finalize(
+            &mut contract,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+        );
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        // 5/8 of round 1 stake
+        assert_eq!(sum_claim_res(d.claim(alice())), 700);
+        // validity bond
+        assert_eq!(sum_claim_res(d.claim(bob())), 280);
+        assert_eq!(sum_claim_res(d.claim(carol())), 0);
+        // 3/8 of round 1 stake
+        assert_eq!(sum_claim_res(d.claim(dave())), 420);
+    }
+
+    #[test]
+    fn d_claim_final_arb() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
         // needed for final arb function
         dr_new(&mut contract);
 
@@ -2080,22 +3661,33 @@ mod mock_token_basic_tests {
                 outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
+        // This round exceeds final arb limit, will be used as signal
+        contract.dr_stake(
+            bob(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+            },
+        );
 
         testing_env!(get_context(alice()));
         contract.dr_final_arbitrator_finalize(
             U64(0),
-            data_request::Outcome::Answer(AnswerType::String("c".to_string())),
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
         );
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        assert_eq!(sum_claim_res(d.claim(alice())), 600);
+        assert_eq!(sum_claim_res(d.claim(bob())), 0);
     }
 
     #[test]
-    #[should_panic(
-        expected = "assertion failed: `(left == right)`\n  left: `\"alice.near\"`,\n right: `\"bob.near\"`: sender is not the final arbitrator of this `DataRequest`, the final arbitrator is: alice.near"
-    )]
-    fn dr_final_arb_non_arb() {
+    fn d_claim_final_arb_extra_round() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
-        let config = config();
+        let mut config = config();
+        config.final_arbitrator_invoke_amount = U128(600);
         let mut contract = Contract::new(whitelist, config);
         // needed for final arb function
         dr_new(&mut contract);
@@ -2108,20 +3700,44 @@ mod mock_token_basic_tests {
                 outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
+        contract.dr_stake(
+            bob(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+            },
+        );
+        // This round exceeds final arb limit, will be used as signal
+        contract.dr_stake(
+            carol(),
+            800,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
 
-        testing_env!(get_context(bob()));
+        testing_env!(get_context(alice()));
         contract.dr_final_arbitrator_finalize(
             U64(0),
-            data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
         );
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        // validity bond
+        assert_eq!(sum_claim_res(d.claim(alice())), 280);
+        assert_eq!(sum_claim_res(d.claim(bob())), 0);
+        // round 1 funds
+        assert_eq!(sum_claim_res(d.claim(carol())), 1120);
     }
 
     #[test]
-    #[should_panic(expected = "Can't stake in finalized DataRequest")]
-    fn dr_final_arb_twice() {
+    fn d_claim_final_arb_extra_round2() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
-        let config = config();
+        let mut config = config();
+        config.final_arbitrator_invoke_amount = U128(600);
         let mut contract = Contract::new(whitelist, config);
         // needed for final arb function
         dr_new(&mut contract);
@@ -2134,7 +3750,6 @@ mod mock_token_basic_tests {
                 outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
-        // This round exceeds final arb limit, will be used as signal
         contract.dr_stake(
             bob(),
             400,
@@ -2143,25 +3758,36 @@ mod mock_token_basic_tests {
                 outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
             },
         );
+        // This round exceeds final arb limit, will be used as signal
+        contract.dr_stake(
+            carol(),
+            800,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
 
         testing_env!(get_context(alice()));
         contract.dr_final_arbitrator_finalize(
             U64(0),
             data_request::Outcome::Answer(AnswerType::String("b".to_string())),
         );
-        contract.dr_final_arbitrator_finalize(
-            U64(0),
-            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
-        );
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        assert_eq!(sum_claim_res(d.claim(alice())), 0);
+        // validity bond (100), round0 (200), round2 (800)
+        assert_eq!(sum_claim_res(d.claim(bob())), 1400);
+        assert_eq!(sum_claim_res(d.claim(carol())), 0);
     }
 
     #[test]
-    fn dr_final_arb_execute() {
+    #[should_panic(expected = "Final arbitrator is invoked for `DataRequest` with id: 0")]
+    fn dr_final_arb_invoked() {
         testing_env!(get_context(token()));
         let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
         let config = config();
         let mut contract = Contract::new(whitelist, config);
-        // needed for final arb function
         dr_new(&mut contract);
 
         contract.dr_stake(
@@ -2172,7 +3798,42 @@ mod mock_token_basic_tests {
                 outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
             },
         );
-        // This round exceeds final arb limit, will be used as signal
+        contract.dr_stake(
+            bob(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+            },
+        );
+        contract.dr_stake(
+            carol(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+    }
+
+    #[test]
+    fn dr_final_arb_contract_invoked() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut config = config();
+        config.invoke_final_arbitrator_contract = true;
+        let mut contract = Contract::new(whitelist, config);
+        dr_new(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+        // bonds round 1 which invokes the final arbitrator contract
         contract.dr_stake(
             bob(),
             400,
@@ -2182,18 +3843,1282 @@ mod mock_token_basic_tests {
             },
         );
 
+        let d = contract.data_requests.get(0).unwrap();
+        assert!(d.final_arbitrator_triggered);
+        assert_eq!(d.resolution_windows.len(), 2);
+
+        // the arbitrator can still answer directly
         testing_env!(get_context(alice()));
         contract.dr_final_arbitrator_finalize(
             U64(0),
             data_request::Outcome::Answer(AnswerType::String("b".to_string())),
         );
+        assert_eq!(
+            contract.data_requests.get(0).unwrap().finalized_outcome,
+            Some(data_request::Outcome::Answer(AnswerType::String(
+                "b".to_string()
+            )))
+        );
+    }
+
+    fn dr_stake_until_final_arb(contract: &mut Contract) {
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+        contract.dr_stake(
+            bob(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+            },
+        );
+    }
 
-        let request: DataRequest = contract.data_requests.get(0).unwrap();
-        assert_eq!(request.resolution_windows.len(), 2);
+    #[test]
+    fn dr_final_arb_fallback_last_bonded() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+        dr_stake_until_final_arb(&mut contract);
+
+        let mut ct: VMContext = get_context(carol());
+        ct.block_timestamp = 5000;
+        testing_env!(ct);
+        contract.dr_final_arbitrator_fallback(U64(0));
+
+        let mut d = contract.data_requests.get(0).unwrap();
         assert_eq!(
-            request.finalized_outcome.unwrap(),
-            data_request::Outcome::Answer(AnswerType::String("b".to_string()))
+            d.finalized_outcome,
+            Some(data_request::Outcome::Answer(AnswerType::String(
+                "b".to_string()
+            )))
         );
+        assert_eq!(sum_claim_res(d.claim(alice())), 0);
+        assert_eq!(sum_claim_res(d.claim(bob())), 600);
+    }
+
+    #[test]
+    fn dr_final_arb_fallback_invalid() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut config = config();
+        config.final_arbitrator_fallback = oracle_config::FinalArbitratorFallback::Invalid;
+        let mut contract = Contract::new(whitelist, config);
+        dr_new(&mut contract);
+        dr_stake_until_final_arb(&mut contract);
+
+        let mut ct: VMContext = get_context(carol());
+        ct.block_timestamp = 5000;
+        testing_env!(ct);
+        contract.dr_final_arbitrator_fallback(U64(0));
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        assert_eq!(d.finalized_outcome, Some(data_request::Outcome::Invalid));
+        assert_eq!(
+            contract.dr_claimable(bob(), U64(0)).stake_token_payout,
+            U128(400)
+        );
+        // nobody resolved the request so the validity bond and fee go back to its creator
+        assert_eq!(d.calc_creator_refund(), 100 + d.request_config.paid_fee);
+        // every round's stake is returned, without a share of the bond or fee
+        let alice_claim = d.claim(alice());
+        assert_eq!(alice_claim.stake_token_payout, 200);
+        assert_eq!(alice_claim.payment_token_payout, 0);
+        let bob_claim = d.claim(bob());
+        assert_eq!(bob_claim.stake_token_payout, 400);
+        assert_eq!(bob_claim.payment_token_payout, 0);
+    }
+
+    fn dr_final_arb_unbonded_invalid(
+        policy: oracle_config::UnbondedOutcomePolicy,
+    ) -> (Contract, DataRequest) {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut config = config();
+        config.unbonded_outcome_policy = policy;
+        let mut contract = Contract::new(whitelist, config);
+        dr_new(&mut contract);
+        contract.dr_stake(
+            carol(),
+            50,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Invalid,
+            },
+        );
+        dr_stake_until_final_arb(&mut contract);
+
+        testing_env!(get_context(alice()));
+        contract.dr_final_arbitrator_finalize(U64(0), data_request::Outcome::Invalid);
+
+        let d = contract.data_requests.get(0).unwrap();
+        assert_eq!(d.finalized_outcome, Some(data_request::Outcome::Invalid));
+        assert!(d.unbonded_final_outcome);
+        (contract, d)
+    }
+
+    #[test]
+    fn dr_final_arb_unbonded_invalid_refund() {
+        let (contract, mut d) =
+            dr_final_arb_unbonded_invalid(oracle_config::UnbondedOutcomePolicy::Refund);
+        assert_eq!(contract.get_treasury_balance(token()), U128(0));
+
+        assert_eq!(sum_claim_res(d.claim(alice())), 200);
+        assert_eq!(sum_claim_res(d.claim(bob())), 400);
+        // carol's stake is returned along with the validity bond
+        let carol_claim = d.claim(carol());
+        assert_eq!(carol_claim.stake_token_payout, 50);
+        assert_eq!(carol_claim.payment_token_payout, 100);
+    }
+
+    #[test]
+    fn dr_final_arb_unbonded_invalid_slash() {
+        let (contract, mut d) =
+            dr_final_arb_unbonded_invalid(oracle_config::UnbondedOutcomePolicy::Slash);
+        assert_eq!(contract.get_treasury_balance(token()), U128(600));
+        assert_eq!(
+            contract.dr_claimable(carol(), U64(0)).payment_token_payout,
+            U128(100)
+        );
+
+        assert_eq!(sum_claim_res(d.claim(alice())), 0);
+        assert_eq!(sum_claim_res(d.claim(bob())), 0);
+        let carol_claim = d.claim(carol());
+        assert_eq!(carol_claim.stake_token_payout, 50);
+        assert_eq!(carol_claim.payment_token_payout, 100);
+    }
+
+    #[test]
+    fn dr_final_arb_unbonded_unstaked_outcome() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+        dr_stake_until_final_arb(&mut contract);
+
+        testing_env!(get_context(alice()));
+        contract.dr_final_arbitrator_finalize(U64(0), data_request::Outcome::Invalid);
+
+        // nobody staked on the final outcome so the validity bond and fee go back to the creator
+        let mut d = contract.data_requests.get(0).unwrap();
+        assert!(d.unbonded_final_outcome);
+        assert_eq!(d.calc_creator_refund(), 100 + d.request_config.paid_fee);
+        assert_eq!(d.claim(alice()).payment_token_payout, 0);
+        assert_eq!(d.claim(bob()).payment_token_payout, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Final arbitrator has until 5000 to finalize")]
+    fn dr_final_arb_fallback_before_deadline() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+        dr_stake_until_final_arb(&mut contract);
+
+        let mut ct: VMContext = get_context(carol());
+        ct.block_timestamp = 4999;
+        testing_env!(ct);
+        contract.dr_final_arbitrator_fallback(U64(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Incompatible outcome")]
+    fn dr_final_arb_invalid_outcome() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let config = config();
+        let mut contract = Contract::new(whitelist, config);
+        // needed for final arb function
+        dr_new(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+
+        testing_env!(get_context(alice()));
+        contract.dr_final_arbitrator_finalize(
+            U64(0),
+            data_request::Outcome::Answer(AnswerType::String("c".to_string())),
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assertion failed: `(left == right)`\n  left: `\"alice.near\"`,\n right: `\"bob.near\"`: sender is not the final arbitrator of this `DataRequest`, the final arbitrator is: alice.near"
+    )]
+    fn dr_final_arb_non_arb() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let config = config();
+        let mut contract = Contract::new(whitelist, config);
+        // needed for final arb function
+        dr_new(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+
+        testing_env!(get_context(bob()));
+        contract.dr_final_arbitrator_finalize(
+            U64(0),
+            data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't stake in finalized DataRequest")]
+    fn dr_final_arb_twice() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let config = config();
+        let mut contract = Contract::new(whitelist, config);
+        // needed for final arb function
+        dr_new(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+        // This round exceeds final arb limit, will be used as signal
+        contract.dr_stake(
+            bob(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+            },
+        );
+
+        testing_env!(get_context(alice()));
+        contract.dr_final_arbitrator_finalize(
+            U64(0),
+            data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+        );
+        contract.dr_final_arbitrator_finalize(
+            U64(0),
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+        );
+    }
+
+    #[test]
+    fn dr_final_arb_execute() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let config = config();
+        let mut contract = Contract::new(whitelist, config);
+        // needed for final arb function
+        dr_new(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+        // This round exceeds final arb limit, will be used as signal
+        contract.dr_stake(
+            bob(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+            },
+        );
+
+        testing_env!(get_context(alice()));
+        contract.dr_final_arbitrator_finalize(
+            U64(0),
+            data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+        );
+
+        let request: DataRequest = contract.data_requests.get(0).unwrap();
+        assert_eq!(request.resolution_windows.len(), 2);
+        assert_eq!(
+            request.finalized_outcome.unwrap(),
+            data_request::Outcome::Answer(AnswerType::String("b".to_string()))
+        );
+    }
+
+    #[test]
+    fn dr_expire_success() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+
+        let mut ct: VMContext = get_context(alice());
+        ct.block_timestamp = 5000;
+        testing_env!(ct);
+        contract.dr_expire(U64(0));
+
+        let request: DataRequest = contract.data_requests.get(0).unwrap();
+        assert_eq!(request.finalized_outcome.unwrap(), Outcome::Invalid);
+    }
+
+    #[test]
+    fn dr_expire_unbonded_stake() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            100,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+
+        let mut ct: VMContext = get_context(alice());
+        ct.block_timestamp = 5000;
+        testing_env!(ct);
+        contract.dr_expire(U64(0));
+
+        // unbonded stake can still be withdrawn after expiry
+        contract.dr_unstake(
+            U64(0),
+            0,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            U128(100),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "DataRequest can only be expired after 5000")]
+    fn dr_expire_before_expiry_time() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+
+        let mut ct: VMContext = get_context(alice());
+        ct.block_timestamp = 4999;
+        testing_env!(ct);
+        contract.dr_expire(U64(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "DataRequest with a bonded outcome can't expire")]
+    fn dr_expire_bonded() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+
+        let mut ct: VMContext = get_context(alice());
+        ct.block_timestamp = 5000;
+        testing_env!(ct);
+        contract.dr_expire(U64(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Final arbitrator is invoked for `DataRequest` with id: 0")]
+    fn dr_expire_final_arbitrator_invoked() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut config = config();
+        config.final_arbitrator_invoke_amount = U128(200);
+        let mut contract = Contract::new(whitelist, config);
+        dr_new(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+        let d = contract.data_requests.get(0).unwrap();
+        assert!(d.final_arbitrator_triggered);
+        assert_eq!(d.resolution_windows.len(), 1);
+
+        let mut ct: VMContext = get_context(alice());
+        ct.block_timestamp = 5000;
+        testing_env!(ct);
+        contract.dr_expire(U64(0));
+    }
+
+    fn dr_new_settlement(contract: &mut Contract, settlement_time: u64) {
+        contract.dr_new(
+            bob(),
+            100,
+            NewDataRequestArgs {
+                sources: Vec::new(),
+                outcomes: Some(vec!["a".to_string(), "b".to_string()].to_vec()),
+                challenge_period: U64(1500),
+                description: Some("a".to_string()),
+                tags: vec!["1".to_string()],
+                data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: Some(U64(settlement_time)),
+                creator: bob(),
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Settlement time can't be after 10000")]
+    fn dr_new_settlement_time_exceed() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_settlement(&mut contract, 10001);
+    }
+
+    #[test]
+    #[should_panic(expected = "DataRequest can only be staked on after 2000")]
+    fn dr_stake_before_settlement() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_settlement(&mut contract, 2000);
+
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1999;
+        testing_env!(ct);
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+    }
+
+    #[test]
+    fn dr_stake_after_settlement() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_settlement(&mut contract, 2000);
+
+        let d = contract.data_requests.get(0).unwrap();
+        assert_eq!(d.request_config.expiry_time, 7000);
+
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 2500;
+        testing_env!(ct);
+        contract.dr_stake(
+            alice(),
+            100,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+
+        // round 0 starts at the settlement time rather than at the first stake
+        let d = contract.data_requests.get(0).unwrap();
+        let window = d.resolution_windows.get(0).unwrap();
+        assert_eq!(window.start_time, 2000);
+        assert_eq!(window.end_time, 3500);
+    }
+
+    #[test]
+    #[should_panic(expected = "Settlement time can't be before 1000")]
+    fn dr_new_settlement_time_past() {
+        let mut ct: VMContext = get_context(token());
+        ct.block_timestamp = 1000;
+        testing_env!(ct);
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_settlement(&mut contract, 999);
+    }
+
+    #[test]
+    fn dr_cancel_success() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+
+        testing_env!(get_context(bob()));
+        contract.dr_cancel(U64(0));
+
+        let d = contract.data_requests.get(0).unwrap();
+        assert!(d.cancelled);
+        assert_eq!(d.finalized_outcome, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the requester or creator can cancel this DataRequest")]
+    fn dr_cancel_unauthorized() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+
+        testing_env!(get_context(carol()));
+        contract.dr_cancel(U64(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "DataRequest that has been staked on can't be cancelled")]
+    fn dr_cancel_staked() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            10,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+
+        testing_env!(get_context(bob()));
+        contract.dr_cancel(U64(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "DataRequest is cancelled")]
+    fn dr_stake_cancelled() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+
+        testing_env!(get_context(bob()));
+        contract.dr_cancel(U64(0));
+
+        testing_env!(get_context(token()));
+        contract.dr_stake(
+            alice(),
+            10,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+    }
+
+    fn range_outcome(value: u128) -> Outcome {
+        Outcome::Answer(AnswerType::Number(AnswerNumberType {
+            value: U128(value),
+            multiplier: U128(1),
+            negative: false,
+        }))
+    }
+
+    fn dr_new_range(contract: &mut Contract, tolerance: Tolerance) {
+        contract.dr_new(
+            bob(),
+            100,
+            NewDataRequestArgs {
+                sources: Vec::new(),
+                outcomes: None,
+                challenge_period: U64(1500),
+                description: Some("a".to_string()),
+                tags: vec!["1".to_string()],
+                data_type: data_request::DataRequestDataType::Range(U128(1), tolerance),
+                reveal_period: None,
+                settlement_time: None,
+                creator: bob(),
+            },
+        );
+    }
+
+    #[test]
+    fn dr_range_outcomes_match() {
+        let absolute = DataRequestDataType::Range(U128(1), Tolerance::Absolute(U128(5)));
+        assert!(absolute.outcomes_match(&range_outcome(100), &range_outcome(105)));
+        assert!(!absolute.outcomes_match(&range_outcome(100), &range_outcome(106)));
+        assert!(!absolute.outcomes_match(&range_outcome(100), &Outcome::Invalid));
+
+        // 1%
+        let relative = DataRequestDataType::Range(U128(1), Tolerance::Relative(100));
+        assert!(relative.outcomes_match(&range_outcome(1000), &range_outcome(990)));
+        assert!(!relative.outcomes_match(&range_outcome(1000), &range_outcome(989)));
+
+        let number = DataRequestDataType::Number(U128(1));
+        assert!(!number.outcomes_match(&range_outcome(100), &range_outcome(101)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Outcome is incompatible for this round")]
+    fn dr_stake_range_within_tolerance() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_range(&mut contract, Tolerance::Absolute(U128(5)));
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(100),
+            },
+        );
+        contract.dr_stake(
+            bob(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(104),
+            },
+        );
+    }
+
+    #[test]
+    fn dr_stake_range_fills_bond_within_tolerance() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_range(&mut contract, Tolerance::Absolute(U128(5)));
+
+        contract.dr_stake(
+            alice(),
+            100,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(100),
+            },
+        );
+        contract.dr_stake(
+            carol(),
+            100,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(104),
+            },
+        );
+
+        // both stakes count towards the first outcome staked within tolerance, which fills the bond
+        let d = contract.data_requests.get(0).unwrap();
+        let round0 = d.resolution_windows.get(0).unwrap();
+        assert_eq!(round0.bonded_outcome, Some(range_outcome(100)));
+        assert_eq!(
+            round0.stake_on_outcome_for(&carol(), &range_outcome(100)),
+            100
+        );
+        assert_eq!(d.resolution_windows.len(), 2);
+
+        finalize(&mut contract, 0);
+        let mut d = contract.data_requests.get(0).unwrap();
+        let alice_claim = d.claim(alice());
+        let carol_claim = d.claim(carol());
+        // carol's stake is paid out as correct stake rather than refunded as unbonded stake
+        assert_eq!(carol_claim.stake_token_payout, 100);
+        assert_eq!(
+            carol_claim.payment_token_payout,
+            alice_claim.payment_token_payout
+        );
+    }
+
+    #[test]
+    fn dr_final_arb_unbonded_range_within_tolerance() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_range(&mut contract, Tolerance::Absolute(U128(5)));
+
+        contract.dr_stake(
+            carol(),
+            50,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(120),
+            },
+        );
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(100),
+            },
+        );
+        contract.dr_stake(
+            bob(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(110),
+            },
+        );
+
+        testing_env!(get_context(alice()));
+        contract.dr_final_arbitrator_finalize(U64(0), range_outcome(122));
+
+        // carol's stake on 120 is within the tolerance of the final outcome so it counts as staked on it
+        let d = contract.data_requests.get(0).unwrap();
+        assert!(d.unbonded_final_outcome);
+        assert_eq!(d.calc_stake_on_final_outcome(Some(&carol())), 50);
+        assert_eq!(d.calc_stake_on_final_outcome(None), 50);
+        assert_eq!(d.calc_creator_refund(), 100);
+    }
+
+    #[test]
+    fn dr_final_arb_range_closest_bonded_outcome() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_range(&mut contract, Tolerance::Absolute(U128(2)));
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(100),
+            },
+        );
+        contract.dr_stake(
+            bob(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(103),
+            },
+        );
+
+        // 102 is within the tolerance of both bonded outcomes, which aren't within the tolerance of each other
+        testing_env!(get_context(alice()));
+        contract.dr_final_arbitrator_finalize(U64(0), range_outcome(102));
+
+        // only the round that bonded the closest outcome is correct
+        let mut d = contract.data_requests.get(0).unwrap();
+        assert!(!d.unbonded_final_outcome);
+        assert_eq!(sum_claim_res(d.claim(alice())), 0);
+        assert_eq!(sum_claim_res(d.claim(bob())), 600);
+    }
+
+    #[test]
+    fn d_claim_range_3rounds() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut config = config();
+        config.final_arbitrator_invoke_amount = U128(1000);
+        let mut contract = Contract::new(whitelist, config);
+        dr_new_range(&mut contract, Tolerance::Absolute(U128(5)));
+
+        contract.dr_stake(
+            bob(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(100),
+            },
+        );
+        contract.dr_stake(
+            carol(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(110),
+            },
+        );
+        contract.dr_stake(
+            dave(),
+            800,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: range_outcome(101),
+            },
+        );
+        finalize(&mut contract, 0);
+
+        let mut d = contract.data_requests.get(0).unwrap();
+        assert_eq!(d.finalized_outcome, Some(range_outcome(101)));
+        // round 0 is within tolerance of the final outcome, 1/5 of round 1 stake
+        assert_eq!(sum_claim_res(d.claim(bob())), 280);
+        assert_eq!(sum_claim_res(d.claim(carol())), 0);
+        // 4/5 of round 1 stake
+        assert_eq!(sum_claim_res(d.claim(dave())), 1120);
+    }
+
+    fn vector_outcome(values: Vec<(u128, bool)>) -> Outcome {
+        Outcome::Answer(AnswerType::Vector(
+            values
+                .into_iter()
+                .map(|(value, negative)| AnswerNumberType {
+                    value: U128(value),
+                    multiplier: U128(1),
+                    negative,
+                })
+                .collect(),
+        ))
+    }
+
+    fn dr_new_vector(contract: &mut Contract) {
+        contract.dr_new(
+            bob(),
+            100,
+            NewDataRequestArgs {
+                sources: vec![
+                    data_request::Source {
+                        end_point: "1".to_string(),
+                        source_path: "1".to_string(),
+                    },
+                    data_request::Source {
+                        end_point: "2".to_string(),
+                        source_path: "2".to_string(),
+                    },
+                ],
+                outcomes: None,
+                challenge_period: U64(1500),
+                description: None,
+                tags: vec!["1".to_string()],
+                data_type: data_request::DataRequestDataType::Vector(U128(1)),
+                reveal_period: None,
+                settlement_time: None,
+                creator: bob(),
+            },
+        );
+    }
+
+    #[test]
+    fn dr_stake_vector() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_vector(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: vector_outcome(vec![(0, true), (5, false)]),
+            },
+        );
+
+        let d = contract.data_requests.get(0).unwrap();
+        let round0 = d.resolution_windows.get(0).unwrap();
+        // `-0` is staked as `0`
+        assert_eq!(
+            round0.bonded_outcome,
+            Some(vector_outcome(vec![(0, false), (5, false)]))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Outcome is incompatible for this round")]
+    fn dr_stake_vector_canonical_same_outcome() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_vector(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: vector_outcome(vec![(0, false), (5, false)]),
+            },
+        );
+        contract.dr_stake(
+            bob(),
+            400,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: vector_outcome(vec![(0, true), (5, false)]),
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_WRONG_VECTOR_LENGTH")]
+    fn dr_stake_vector_wrong_length() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_vector(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: vector_outcome(vec![(5, false)]),
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_WRONG_OUTCOME_TYPE")]
+    fn dr_stake_vector_on_number_request() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_range(&mut contract, Tolerance::Absolute(U128(5)));
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: vector_outcome(vec![(5, false)]),
+            },
+        );
+    }
+
+    fn dr_new_commit_reveal(contract: &mut Contract) {
+        contract.dr_new(
+            bob(),
+            100,
+            NewDataRequestArgs {
+                sources: Vec::new(),
+                outcomes: Some(vec!["a".to_string(), "b".to_string()].to_vec()),
+                challenge_period: U64(1500),
+                description: Some("a".to_string()),
+                tags: vec!["1".to_string()],
+                data_type: data_request::DataRequestDataType::String,
+                creator: bob(),
+                reveal_period: Some(U64(1000)),
+                settlement_time: None,
+            },
+        );
+    }
+
+    fn dr_commit(contract: &mut Contract, sender: AccountId, amount: Balance, outcome: &str) {
+        let outcome = data_request::Outcome::Answer(AnswerType::String(outcome.to_string()));
+        // commitments go into the last round, or round 0 if it isn't opened yet
+        let round = contract
+            .data_requests
+            .get(0)
+            .unwrap()
+            .resolution_windows
+            .len()
+            .saturating_sub(1) as u16;
+        let commitment = contract.dr_commitment_hash(
+            sender.to_string(),
+            U64(0),
+            round,
+            outcome,
+            "salt".to_string(),
+        );
+        contract.dr_commit(
+            sender,
+            amount,
+            CommitDataRequestArgs {
+                id: U64(0),
+                commitment,
+            },
+        );
+    }
+
+    #[test]
+    fn dr_commit_reveal_success() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_commit_reveal(&mut contract);
+        dr_commit(&mut contract, alice(), 300, "a");
+
+        let round0 = contract
+            .data_requests
+            .get(0)
+            .unwrap()
+            .resolution_windows
+            .get(0)
+            .unwrap();
+        assert_eq!(round0.commit_end_time, Some(1500));
+        assert_eq!(round0.end_time, 2500);
+        assert_eq!(round0.bonded_outcome, None);
+
+        let mut ct = get_context(alice());
+        ct.block_timestamp = 1600;
+        testing_env!(ct);
+        contract.dr_reveal(
+            U64(0),
+            0,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            "salt".to_string(),
+        );
+
+        let d = contract.data_requests.get(0).unwrap();
+        let round0 = d.resolution_windows.get(0).unwrap();
+        assert_eq!(
+            round0.bonded_outcome,
+            Some(data_request::Outcome::Answer(AnswerType::String(
+                "a".to_string()
+            )))
+        );
+        assert_eq!(
+            round0.user_to_outcome_to_stake.get(&alice()).unwrap().get(
+                &data_request::Outcome::Answer(AnswerType::String("a".to_string()))
+            ),
+            Some(200)
+        );
+        assert!(round0.user_to_commitment.get(&alice()).is_none());
+
+        // the next round only starts once round 0's reveal phase has ended
+        let round1 = d.resolution_windows.get(1).unwrap();
+        assert_eq!(round1.start_time, 2500);
+        assert_eq!(round1.commit_end_time, Some(3500));
+        assert_eq!(round1.end_time, 4500);
+    }
+
+    #[test]
+    fn dr_reveal_after_round_bonded() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_commit_reveal(&mut contract);
+        dr_commit(&mut contract, alice(), 200, "a");
+        dr_commit(&mut contract, carol(), 200, "b");
+
+        let mut ct = get_context(alice());
+        ct.block_timestamp = 1600;
+        testing_env!(ct);
+        contract.dr_reveal(
+            U64(0),
+            0,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            "salt".to_string(),
+        );
+
+        // carol can still reveal in round 0 after it bonded, her stake is returned since it can't change the round's outcome
+        let mut ct = get_context(carol());
+        ct.block_timestamp = 1700;
+        testing_env!(ct);
+        contract.dr_reveal(
+            U64(0),
+            0,
+            data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+            "salt".to_string(),
+        );
+
+        let d = contract.data_requests.get(0).unwrap();
+        let round0 = d.resolution_windows.get(0).unwrap();
+        assert!(round0.user_to_commitment.get(&carol()).is_none());
+        assert_eq!(round0.total_stake_for(&carol()), 0);
+        assert_eq!(
+            round0.bonded_outcome,
+            Some(data_request::Outcome::Answer(AnswerType::String(
+                "a".to_string()
+            )))
+        );
+        assert_eq!(d.resolution_windows.len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Commit phase starts at 2500")]
+    fn dr_commit_before_round_start() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_commit_reveal(&mut contract);
+        dr_commit(&mut contract, alice(), 200, "a");
+
+        let mut ct = get_context(alice());
+        ct.block_timestamp = 1600;
+        testing_env!(ct);
+        contract.dr_reveal(
+            U64(0),
+            0,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            "salt".to_string(),
+        );
+
+        let mut ct = get_context(token());
+        ct.block_timestamp = 1700;
+        testing_env!(ct);
+        dr_commit(&mut contract, carol(), 400, "b");
+    }
+
+    #[test]
+    #[should_panic(expected = "Stakes on this DataRequest need to be committed and revealed")]
+    fn dr_stake_commit_reveal() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_commit_reveal(&mut contract);
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "DataRequest does not use commit-reveal")]
+    fn dr_commit_no_commit_reveal() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new(&mut contract);
+        dr_commit(&mut contract, alice(), 200, "a");
+    }
+
+    #[test]
+    #[should_panic(expected = "Can only reveal during the reveal phase")]
+    fn dr_reveal_commit_phase() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_commit_reveal(&mut contract);
+        dr_commit(&mut contract, alice(), 200, "a");
+
+        testing_env!(get_context(alice()));
+        contract.dr_reveal(
+            U64(0),
+            0,
+            data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            "salt".to_string(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Revealed outcome and salt do not match commitment")]
+    fn dr_reveal_wrong_outcome() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_commit_reveal(&mut contract);
+        dr_commit(&mut contract, alice(), 200, "a");
+
+        let mut ct = get_context(alice());
+        ct.block_timestamp = 1600;
+        testing_env!(ct);
+        contract.dr_reveal(
+            U64(0),
+            0,
+            data_request::Outcome::Answer(AnswerType::String("b".to_string())),
+            "salt".to_string(),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Revealed outcome and salt do not match commitment")]
+    fn dr_reveal_copied_commitment() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_commit_reveal(&mut contract);
+        dr_commit(&mut contract, alice(), 200, "a");
+
+        // carol copies alice's commitment
+        let outcome = data_request::Outcome::Answer(AnswerType::String("a".to_string()));
+        let copied =
+            contract.dr_commitment_hash(alice(), U64(0), 0, outcome.clone(), "salt".to_string());
+        contract.dr_commit(
+            carol(),
+            200,
+            CommitDataRequestArgs {
+                id: U64(0),
+                commitment: copied,
+            },
+        );
+
+        // and reveals alice's outcome and salt once alice published them
+        let mut ct = get_context(carol());
+        ct.block_timestamp = 1600;
+        testing_env!(ct);
+        contract.dr_reveal(U64(0), 0, outcome, "salt".to_string());
+    }
+
+    #[test]
+    fn dr_commitment_hash_binds_staker_and_round() {
+        testing_env!(get_context(token()));
+        let outcome = data_request::Outcome::Answer(AnswerType::String("a".to_string()));
+        let hash = commitment_hash(&alice(), 0, 0, &outcome, "salt");
+        assert_ne!(hash, commitment_hash(&carol(), 0, 0, &outcome, "salt"));
+        assert_ne!(hash, commitment_hash(&alice(), 1, 0, &outcome, "salt"));
+        assert_ne!(hash, commitment_hash(&alice(), 0, 1, &outcome, "salt"));
+    }
+
+    #[test]
+    fn dr_withdraw_unrevealed_success() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_commit_reveal(&mut contract);
+        dr_commit(&mut contract, alice(), 200, "a");
+
+        let mut ct = get_context(alice());
+        ct.block_timestamp = 2500;
+        testing_env!(ct);
+        contract.dr_withdraw_unrevealed(U64(0), 0, alice());
+
+        let round0 = contract
+            .data_requests
+            .get(0)
+            .unwrap()
+            .resolution_windows
+            .get(0)
+            .unwrap();
+        assert!(round0.user_to_commitment.get(&alice()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Commitment can still be revealed")]
+    fn dr_withdraw_unrevealed_reveal_phase() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        dr_new_commit_reveal(&mut contract);
+        dr_commit(&mut contract, alice(), 200, "a");
+
+        let mut ct = get_context(alice());
+        ct.block_timestamp = 1600;
+        testing_env!(ct);
+        contract.dr_withdraw_unrevealed(U64(0), 0, alice());
+    }
+
+    #[test]
+    fn dr_withdraw_unrevealed_forfeited() {
+        testing_env!(get_context(token()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut config = config();
+        config.forfeit_unrevealed_stakes = true;
+        let mut contract = Contract::new(whitelist, config);
+        dr_new_commit_reveal(&mut contract);
+        dr_commit(&mut contract, alice(), 200, "a");
+
+        let mut ct = get_context(alice());
+        ct.block_timestamp = 2500;
+        testing_env!(ct);
+        contract.dr_withdraw_unrevealed(U64(0), 0, alice());
+
+        assert_eq!(contract.get_treasury_balance(token()), U128(200));
     }
 
     #[test]
@@ -2222,6 +5147,7 @@ mod mock_token_basic_tests {
             account_id: bob(),
             stake_multiplier: None,
             code_base_url: None,
+            total_value_secured: Some(U128(200)),
         };
         let fixed_fee = 20;
         let whitelist = Some(vec![bob_requester, registry_entry(carol())]);
@@ -2239,6 +5165,8 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
@@ -2,15 +2,65 @@ use crate::*;
 use near_sdk::serde::{Deserialize, Serialize};
 
 const MAX_RESOLUTION_FEE_PERCENTAGE: u32 = 5000; // 5% in 1e5
+const FEE_PERCENTAGE_DIVISOR: u128 = 100_000;
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
 pub struct FeeConfig {
     // total market cap of FLUX/stake_token denominated in payment_token
     pub flux_market_cap: U128,
     // total value staked (TVS) of all request interfaces; denominated in payment_token
+    // set by governance and used as a floor for the sum of the values reported by whitelisted requesters
     pub total_value_staked: U128,
     // global percentage of TVS to pay out to resolutors; denominated in 1e5 so 1 = 0.001%, 100000 = 100%
     pub resolution_fee_percentage: u32,
+    // fee charged per request regardless of the value secured by its requester; denominated in payment_token
+    pub min_resolution_fee: U128,
+}
+
+impl FeeConfig {
+    // @returns the TVS requests are charged against, the TVS reported by requesters with governance's TVS as a floor
+    pub fn calc_total_value_staked(&self, total_value_secured: Balance) -> Balance {
+        std::cmp::max(u128::from(self.total_value_staked), total_value_secured)
+    }
+
+    pub fn assert_valid_total_value_staked(&self, total_value_secured: Balance) {
+        assert!(
+            self.calc_total_value_staked(total_value_secured) < u128::from(self.flux_market_cap),
+            "TVS must be lower than market cap"
+        );
+    }
+
+    /**
+     * @notice Calculates the resolution fee owed by a requester for a new `DataRequest`
+     * @param total_value_secured the sum of the values secured by all whitelisted requesters, denominated in payment_token
+     * @param requester_tvs the value secured by the requester, denominated in payment_token
+     * @returns `resolution_fee_percentage` of TVS scaled by the requester's share of TVS, at least `min_resolution_fee` so requesters
+     * that report no or a zero TVS still pay for resolution
+     */
+    pub fn calc_resolution_fee(
+        &self,
+        total_value_secured: Balance,
+        requester_tvs: Balance,
+    ) -> Balance {
+        let min_resolution_fee = u128::from(self.min_resolution_fee);
+        let total_value_staked = self.calc_total_value_staked(total_value_secured);
+        if total_value_staked == 0 {
+            return min_resolution_fee;
+        }
+
+        let fee_pool = helpers::calc_product(
+            total_value_staked,
+            self.resolution_fee_percentage.into(),
+            FEE_PERCENTAGE_DIVISOR,
+        );
+        // a requester can never secure more than the total value staked
+        let requester_share = std::cmp::min(requester_tvs, total_value_staked);
+
+        std::cmp::max(
+            helpers::calc_product(fee_pool, requester_share, total_value_staked),
+            min_resolution_fee,
+        )
+    }
 }
 
 #[near_bindgen]
@@ -18,15 +68,27 @@ impl Contract {
     // @notice sets FLUX market cap, TVS, and fee percentage by updating current oracle config
     // replaces the `fee` field inside oracle config with updated FeeConfig
     pub fn update_fee_config(&mut self, new_fee_config: FeeConfig) {
-        self.assert_gov();
+        self.assert_role_immediate(Role::FeeManager);
 
         let initial_storage = env::storage_usage();
 
-        assert!(
-            u128::from(new_fee_config.total_value_staked)
-                < u128::from(new_fee_config.flux_market_cap),
-            "TVS must be lower than market cap"
-        );
+        self.apply_fee_config(new_fee_config);
+
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+    }
+
+    pub fn get_total_value_staked(&self) -> U128 {
+        U128(
+            self.get_config()
+                .fee
+                .calc_total_value_staked(self.total_value_secured),
+        )
+    }
+}
+
+impl Contract {
+    pub fn apply_fee_config(&mut self, new_fee_config: FeeConfig) {
+        new_fee_config.assert_valid_total_value_staked(self.total_value_secured);
         assert!(
             new_fee_config.resolution_fee_percentage <= MAX_RESOLUTION_FEE_PERCENTAGE,
             "Exceeds max resolution fee percentage"
@@ -39,7 +101,25 @@ impl Contract {
             .replace(self.configs.len() - 1, &updated_config);
 
         logger::log_oracle_config(&updated_config, self.configs.len() - 1);
-        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+    }
+
+    /**
+     * @notice Updates the aggregated TVS after the value secured by a requester changed, new requests are charged
+     * against it once it exceeds governance's TVS
+     */
+    pub fn update_total_value_secured(
+        &mut self,
+        prev_requester_tvs: Balance,
+        requester_tvs: Balance,
+    ) {
+        if prev_requester_tvs == requester_tvs {
+            return;
+        }
+
+        self.total_value_secured = self.total_value_secured + requester_tvs - prev_requester_tvs;
+        self.get_config()
+            .fee
+            .assert_valid_total_value_staked(self.total_value_secured);
     }
 }
 
@@ -51,10 +131,6 @@ mod mock_token_basic_tests {
     use near_sdk::MockedBlockchain;
     use near_sdk::{testing_env, VMContext};
 
-    fn alice() -> AccountId {
-        "alice.near".to_string()
-    }
-
     fn bob() -> AccountId {
         "bob.near".to_string()
     }
@@ -70,19 +146,13 @@ mod mock_token_basic_tests {
     fn config(gov: AccountId) -> oracle_config::OracleConfig {
         oracle_config::OracleConfig {
             gov,
-            final_arbitrator: alice(),
-            payment_token: token(),
-            stake_token: token(),
             validity_bond: U128(0),
-            max_outcomes: 8,
-            default_challenge_window_duration: U64(1000),
-            min_initial_challenge_window_duration: U64(1000),
             final_arbitrator_invoke_amount: U128(25_000_000_000_000_000_000_000_000_000_000),
             fee: FeeConfig {
-                flux_market_cap: U128(50000),
-                total_value_staked: U128(10000),
                 resolution_fee_percentage: 5000, // 5%
+                ..test_utils::config().fee
             },
+            ..test_utils::config()
         }
     }
 
@@ -115,6 +185,7 @@ mod mock_token_basic_tests {
             flux_market_cap: U128(1234),
             total_value_staked: U128(123),
             resolution_fee_percentage: 999, // .999%
+            min_resolution_fee: U128(0),
         };
         contract.update_fee_config(new_fee_config);
     }
@@ -129,6 +200,58 @@ mod mock_token_basic_tests {
             flux_market_cap: U128(1234),
             total_value_staked: U128(123),
             resolution_fee_percentage: 999, // .999%
+            min_resolution_fee: U128(0),
+        };
+        contract.update_fee_config(new_fee_config);
+    }
+
+    #[test]
+    fn g_calc_resolution_fee() {
+        let fee_config = config(gov()).fee;
+        // 5% of 10000 TVS scaled by 20% share
+        assert_eq!(fee_config.calc_resolution_fee(0, 2000), 100);
+        assert_eq!(fee_config.calc_resolution_fee(0, 0), 0);
+        // share is capped at 100% of TVS
+        assert_eq!(fee_config.calc_resolution_fee(0, 20000), 500);
+        // reported TVS above governance's TVS is used instead
+        assert_eq!(fee_config.calc_resolution_fee(20000, 20000), 1000);
+    }
+
+    #[test]
+    fn g_calc_resolution_fee_minimum() {
+        let fee_config = FeeConfig {
+            min_resolution_fee: U128(50),
+            ..config(gov()).fee
+        };
+        // a requester without a reported TVS pays the minimum fee
+        let requester = Requester {
+            contract_name: bob(),
+            account_id: bob(),
+            stake_multiplier: None,
+            code_base_url: None,
+            total_value_secured: None,
+        };
+        assert_eq!(
+            fee_config.calc_resolution_fee(0, requester.get_total_value_secured()),
+            50
+        );
+        // above the minimum the TVS based fee is charged
+        assert_eq!(fee_config.calc_resolution_fee(0, 2000), 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "TVS must be lower than market cap")]
+    fn g_update_fee_below_reported_tvs() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config(gov()));
+        contract.update_total_value_secured(0, 2000);
+
+        // governance's TVS is fine but the reported TVS exceeds the new market cap
+        let new_fee_config = FeeConfig {
+            flux_market_cap: U128(1234),
+            total_value_staked: U128(123),
+            resolution_fee_percentage: 999, // .999%
+            min_resolution_fee: U128(0),
         };
         contract.update_fee_config(new_fee_config);
     }
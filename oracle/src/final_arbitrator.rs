@@ -0,0 +1,26 @@
+use crate::*;
+use data_request::DataRequestSummary;
+use near_sdk::{ext_contract, Gas, Promise};
+
+const GAS_BASE_ARBITRATE: Gas = 50_000_000_000_000;
+
+#[ext_contract]
+pub trait FinalArbitratorContractExtern {
+    // Should return `Some(outcome)` to finalize right away, or `None` and call `dr_final_arbitrator_finalize` once decided
+    fn dr_arbitrate(request: DataRequestSummary, contested_outcomes: Vec<Outcome>);
+}
+
+pub fn arbitrate(
+    final_arbitrator: &AccountId,
+    request: DataRequestSummary,
+    contested_outcomes: Vec<Outcome>,
+) -> Promise {
+    final_arbitrator_contract_extern::dr_arbitrate(
+        request,
+        contested_outcomes,
+        // NEAR params
+        final_arbitrator,
+        0,
+        GAS_BASE_ARBITRATE,
+    )
+}
@@ -6,7 +6,7 @@ pub trait FungibleToken {
     fn ft_balance_of(&self, account_id: AccountId);
 }
 
-const GAS_BASE_TRANSFER: Gas = 5_000_000_000_000;
+pub const GAS_BASE_TRANSFER: Gas = 5_000_000_000_000;
 
 pub fn fungible_token_transfer(
     token_account_id: AccountId,
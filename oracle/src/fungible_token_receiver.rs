@@ -8,6 +8,10 @@ use near_sdk::PromiseOrValue;
 pub enum Payload {
     NewDataRequest(NewDataRequestArgs),
     StakeDataRequest(StakeDataRequestArgs),
+    CommitDataRequest(CommitDataRequestArgs),
+    NewSubscription(subscription::NewSubscriptionArgs),
+    TopUpSubscription(subscription::TopUpSubscriptionArgs),
+    RegisterRequester(requester_registration::RegisterRequesterArgs),
 }
 
 pub trait FungibleTokenReceiver {
@@ -41,6 +45,18 @@ impl FungibleTokenReceiver for Contract {
             Payload::StakeDataRequest(payload) => {
                 self.dr_stake(sender_id.clone(), amount.into(), payload)
             }
+            Payload::CommitDataRequest(payload) => {
+                self.dr_commit(sender_id.clone(), amount.into(), payload)
+            }
+            Payload::NewSubscription(payload) => PromiseOrValue::Value(U128(
+                self.subscription_new(sender_id.clone(), amount.into(), payload),
+            )),
+            Payload::TopUpSubscription(payload) => PromiseOrValue::Value(U128(
+                self.subscription_top_up(sender_id.clone(), amount.into(), payload),
+            )),
+            Payload::RegisterRequester(payload) => PromiseOrValue::Value(U128(
+                self.requester_register(sender_id.clone(), amount.into(), payload),
+            )),
         };
 
         self.use_storage(&sender_id, initial_storage_usage, account.available);
@@ -77,10 +93,6 @@ mod mock_token_basic_tests {
         "token.near".to_string()
     }
 
-    fn gov() -> AccountId {
-        "gov.near".to_string()
-    }
-
     fn to_valid(account: AccountId) -> ValidAccountId {
         account.try_into().expect("invalid account")
     }
@@ -91,25 +103,18 @@ mod mock_token_basic_tests {
             account_id: account.clone(),
             stake_multiplier: None,
             code_base_url: None,
+            total_value_secured: None,
         }
     }
 
     fn config() -> oracle_config::OracleConfig {
         oracle_config::OracleConfig {
-            gov: gov(),
-            final_arbitrator: alice(),
-            payment_token: token(),
-            stake_token: token(),
             validity_bond: U128(0),
-            max_outcomes: 8,
-            default_challenge_window_duration: U64(1000),
-            min_initial_challenge_window_duration: U64(1000),
-            final_arbitrator_invoke_amount: U128(250),
             fee: FeeConfig {
-                flux_market_cap: U128(50000),
-                total_value_staked: U128(10000),
                 resolution_fee_percentage: 5000, // 5%
+                ..test_utils::config().fee
             },
+            ..test_utils::config()
         }
     }
 
@@ -153,6 +158,8 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
@@ -182,6 +189,8 @@ mod mock_token_basic_tests {
                 description: Some("a".to_string()),
                 tags: vec!["1".to_string()],
                 data_type: data_request::DataRequestDataType::String,
+                reveal_period: None,
+                settlement_time: None,
                 creator: bob(),
             },
         );
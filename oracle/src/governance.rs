@@ -0,0 +1,515 @@
+use crate::*;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+use crate::fee_config::FeeConfig;
+use crate::oracle_config::OracleConfig;
+use crate::whitelist::RequesterPatch;
+
+// Time after `execution_time` during which a proposal can be executed, after that it expires
+pub const GOVERNANCE_GRACE_PERIOD: Duration = 1_209_600_000_000_000;
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum GovernanceAction {
+    SetConfig(OracleConfig),
+    UpdateFeeConfig(FeeConfig),
+    AddToWhitelist(Requester),
+    RemoveFromWhitelist(Requester),
+    UpdateRequester(AccountId, RequesterPatch),
+    SlashRequesterBond(AccountId),
+    GrantRole(Role, AccountId),
+    RevokeRole(Role, AccountId),
+    Upgrade(Base64VecU8), // sha256 of the code that can be deployed through `upgrade` once executable
+}
+
+impl GovernanceAction {
+    // @returns role that is allowed to queue and cancel the action next to the governance contract, `None` if only governance can
+    fn role(&self) -> Option<Role> {
+        match self {
+            GovernanceAction::SetConfig(_) => Some(Role::ConfigAdmin),
+            GovernanceAction::UpdateFeeConfig(_) => Some(Role::FeeManager),
+            GovernanceAction::AddToWhitelist(_)
+            | GovernanceAction::RemoveFromWhitelist(_)
+            | GovernanceAction::UpdateRequester(_, _)
+            | GovernanceAction::SlashRequesterBond(_) => Some(Role::WhitelistManager),
+            GovernanceAction::GrantRole(_, _) | GovernanceAction::RevokeRole(_, _) => None,
+            GovernanceAction::Upgrade(_) => Some(Role::Upgrader),
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalStatus {
+    Queued,
+    Executed,
+    Cancelled,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Proposal {
+    pub action: GovernanceAction,
+    pub proposer: AccountId, // account that queued the action, next to the governance contract the only one that can cancel it
+    pub execution_time: WrappedTimestamp, // time after which anyone can execute the action
+    pub status: ProposalStatus,
+}
+
+impl Proposal {
+    fn assert_queued(&self) {
+        assert_eq!(
+            self.status,
+            ProposalStatus::Queued,
+            "Proposal is not queued"
+        );
+    }
+
+    fn assert_executable(&self) {
+        self.assert_queued();
+        assert!(
+            env::block_timestamp() >= u64::from(self.execution_time),
+            "Proposal can only be executed after {}",
+            u64::from(self.execution_time)
+        );
+        let expiry_time = u64::from(self.execution_time) + GOVERNANCE_GRACE_PERIOD;
+        assert!(
+            env::block_timestamp() < expiry_time,
+            "Proposal expired at {}",
+            expiry_time
+        );
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /**
+     * @notice Queues a governance action that anyone can execute after `execution_time` until `GOVERNANCE_GRACE_PERIOD` has passed
+     * @returns id of the proposal
+     */
+    #[payable]
+    pub fn gov_queue(&mut self, action: GovernanceAction, execution_time: WrappedTimestamp) -> U64 {
+        self.assert_can_manage(&action);
+
+        let initial_storage = env::storage_usage();
+
+        let min_execution_time =
+            env::block_timestamp() + u64::from(self.get_config().governance_delay);
+        assert!(
+            u64::from(execution_time) >= min_execution_time,
+            "Execution time should be at least {}",
+            min_execution_time
+        );
+
+        let proposal = Proposal {
+            action,
+            proposer: env::predecessor_account_id(),
+            execution_time,
+            status: ProposalStatus::Queued,
+        };
+        self.proposals.push(&proposal);
+        let id = self.proposals.len() - 1;
+
+        logger::log_proposal(id, &proposal);
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+
+        U64(id)
+    }
+
+    /**
+     * @notice Applies a queued governance action after its execution time and before it expires, upgrades are executed through
+     * `upgrade` instead
+     */
+    #[payable]
+    pub fn gov_execute(&mut self, proposal_id: U64) {
+        let initial_storage = env::storage_usage();
+
+        let mut proposal = self.proposal_get_expect(proposal_id);
+        proposal.assert_executable();
+        proposal.status = ProposalStatus::Executed;
+
+        self.proposals.replace(proposal_id.into(), &proposal);
+        logger::log_proposal(proposal_id.into(), &proposal);
+
+        match proposal.action {
+            GovernanceAction::SetConfig(config) => self.apply_config(config),
+            GovernanceAction::UpdateFeeConfig(fee_config) => self.apply_fee_config(fee_config),
+            GovernanceAction::AddToWhitelist(requester) => self.whitelist_add(requester),
+            GovernanceAction::RemoveFromWhitelist(requester) => self.whitelist_remove(requester),
+            GovernanceAction::UpdateRequester(account_id, patch) => {
+                self.whitelist_update(account_id, patch)
+            }
+            GovernanceAction::SlashRequesterBond(account_id) => {
+                self.requester_bond_slash(account_id)
+            }
+            GovernanceAction::GrantRole(role, account_id) => self.role_grant(role, account_id),
+            GovernanceAction::RevokeRole(role, account_id) => self.role_revoke(role, account_id),
+            GovernanceAction::Upgrade(_) => panic!("Upgrades are executed through `upgrade`"),
+        }
+
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+    }
+
+    /**
+     * @notice Cancels a queued governance action before its execution time, only callable by the governance contract or the proposer
+     * while it holds the role that can queue the action
+     */
+    pub fn gov_cancel(&mut self, proposal_id: U64) {
+        let mut proposal = self.proposal_get_expect(proposal_id);
+        self.assert_can_manage(&proposal.action);
+        let gov = self.get_config().gov;
+        let sender = env::predecessor_account_id();
+        assert!(
+            sender == gov || sender == proposal.proposer,
+            "This proposal can only be cancelled by the governance contract {} or its proposer {}",
+            gov,
+            proposal.proposer
+        );
+        proposal.assert_queued();
+        assert!(
+            env::block_timestamp() < u64::from(proposal.execution_time),
+            "Proposal can only be cancelled before {}",
+            u64::from(proposal.execution_time)
+        );
+        proposal.status = ProposalStatus::Cancelled;
+        self.proposals.replace(proposal_id.into(), &proposal);
+
+        logger::log_proposal(proposal_id.into(), &proposal);
+    }
+
+    pub fn get_proposal(&self, proposal_id: U64) -> Option<Proposal> {
+        self.proposals.get(proposal_id.into())
+    }
+}
+
+impl Contract {
+    // @notice Checks `role` can apply an action without queueing it, which is only the case without a `governance_delay`
+    pub fn assert_role_immediate(&self, role: Role) {
+        self.assert_role(role);
+        self.assert_no_governance_delay();
+    }
+
+    // @notice Checks the governance contract can apply an action without queueing it, which is only the case without a `governance_delay`
+    pub fn assert_gov_immediate(&self) {
+        self.assert_gov();
+        self.assert_no_governance_delay();
+    }
+
+    fn assert_no_governance_delay(&self) {
+        assert_eq!(
+            u64::from(self.get_config().governance_delay),
+            0,
+            "Governance actions need to be queued through `gov_queue`"
+        );
+    }
+
+    fn assert_can_manage(&self, action: &GovernanceAction) {
+        match action.role() {
+            Some(role) => self.assert_role(role),
+            None => self.assert_gov(),
+        }
+    }
+
+    /**
+     * @notice Checks the code passed to `upgrade` may be deployed, with a `governance_delay` it has to match an executable upgrade proposal
+     * which is marked executed, otherwise only the governance contract or an upgrader can upgrade
+     */
+    pub fn assert_can_upgrade(&mut self, code: &[u8]) {
+        if u64::from(self.get_config().governance_delay) == 0 {
+            self.assert_role(Role::Upgrader);
+            return;
+        }
+
+        let code_hash = env::sha256(code);
+        let (id, mut proposal) = self
+            .proposals
+            .iter()
+            .enumerate()
+            .find(|(_, proposal)| match &proposal.action {
+                GovernanceAction::Upgrade(hash) => {
+                    proposal.status == ProposalStatus::Queued && hash.0 == code_hash
+                }
+                _ => false,
+            })
+            .expect("No queued upgrade proposal for this code");
+        proposal.assert_executable();
+        proposal.status = ProposalStatus::Executed;
+        self.proposals.replace(id as u64, &proposal);
+
+        logger::log_proposal(id as u64, &proposal);
+    }
+
+    fn proposal_get_expect(&self, id: U64) -> Proposal {
+        self.proposals
+            .get(id.into())
+            .expect("ERR_PROPOSAL_NOT_FOUND")
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod mock_token_basic_tests {
+    use super::*;
+    use near_sdk::MockedBlockchain;
+    use near_sdk::{testing_env, VMContext};
+
+    fn alice() -> AccountId {
+        "alice.near".to_string()
+    }
+
+    fn bob() -> AccountId {
+        "bob.near".to_string()
+    }
+
+    fn gov() -> AccountId {
+        "gov.near".to_string()
+    }
+
+    fn registry_entry(account: AccountId) -> Requester {
+        Requester {
+            contract_name: account.clone(),
+            account_id: account.clone(),
+            stake_multiplier: None,
+            code_base_url: None,
+            total_value_secured: None,
+        }
+    }
+
+    fn config() -> oracle_config::OracleConfig {
+        oracle_config::OracleConfig {
+            governance_delay: U64(1000),
+            ..test_utils::config()
+        }
+    }
+
+    fn get_context(predecessor_account_id: AccountId) -> VMContext {
+        VMContext {
+            current_account_id: alice(),
+            signer_account_id: alice(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id,
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 10000 * 10u128.pow(24),
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 1000 * 10u128.pow(24),
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        }
+    }
+
+    fn queue_new_config(contract: &mut Contract) -> U64 {
+        let mut new_config = config();
+        new_config.max_outcomes = 4;
+        contract.gov_queue(GovernanceAction::SetConfig(new_config), U64(1000))
+    }
+
+    #[test]
+    fn gov_queue_and_execute() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(Some(vec![registry_entry(bob())]), config());
+        let id = queue_new_config(&mut contract);
+        contract.gov_queue(
+            GovernanceAction::AddToWhitelist(registry_entry(alice())),
+            U64(1000),
+        );
+
+        let mut ct = get_context(bob());
+        ct.block_timestamp = 1000;
+        testing_env!(ct);
+        contract.gov_execute(id);
+        contract.gov_execute(U64(1));
+
+        assert_eq!(contract.get_config().max_outcomes, 4);
+        assert!(contract.whitelist.contains(alice()));
+        assert_eq!(
+            contract.get_proposal(id).unwrap().status,
+            ProposalStatus::Executed
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal can only be executed after 1000")]
+    fn gov_execute_before_execution_time() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        let id = queue_new_config(&mut contract);
+
+        let mut ct = get_context(bob());
+        ct.block_timestamp = 999;
+        testing_env!(ct);
+        contract.gov_execute(id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Execution time should be at least 1000")]
+    fn gov_queue_below_delay() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        contract.gov_queue(GovernanceAction::SetConfig(config()), U64(999));
+    }
+
+    #[test]
+    #[should_panic(expected = "This method is only callable by the governance contract gov.near")]
+    fn gov_queue_non_gov() {
+        testing_env!(get_context(bob()));
+        let mut contract = Contract::new(None, config());
+        queue_new_config(&mut contract);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal is not queued")]
+    fn gov_cancel_and_execute() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        let id = queue_new_config(&mut contract);
+        contract.gov_cancel(id);
+        assert_eq!(
+            contract.get_proposal(id).unwrap().status,
+            ProposalStatus::Cancelled
+        );
+
+        let mut ct = get_context(bob());
+        ct.block_timestamp = 1000;
+        testing_env!(ct);
+        contract.gov_execute(id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal can only be cancelled before 1000")]
+    fn gov_cancel_after_execution_time() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        let id = queue_new_config(&mut contract);
+
+        let mut ct = get_context(gov());
+        ct.block_timestamp = 1000;
+        testing_env!(ct);
+        contract.gov_cancel(id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal expired at 1209600000001000")]
+    fn gov_execute_after_grace_period() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        let id = queue_new_config(&mut contract);
+
+        let mut ct = get_context(bob());
+        ct.block_timestamp = 1000 + GOVERNANCE_GRACE_PERIOD;
+        testing_env!(ct);
+        contract.gov_execute(id);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "This proposal can only be cancelled by the governance contract gov.near or its proposer alice.near"
+    )]
+    fn gov_cancel_non_proposer() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        let id = contract.gov_queue(
+            GovernanceAction::GrantRole(Role::ConfigAdmin, alice()),
+            U64(1000),
+        );
+        contract.gov_queue(
+            GovernanceAction::GrantRole(Role::ConfigAdmin, bob()),
+            U64(1000),
+        );
+
+        let mut ct = get_context(bob());
+        ct.block_timestamp = 1000;
+        testing_env!(ct);
+        contract.gov_execute(id);
+        contract.gov_execute(U64(1));
+
+        testing_env!(get_context(alice()));
+        let id = queue_new_config(&mut contract);
+        assert_eq!(contract.get_proposal(id).unwrap().proposer, alice());
+
+        // bob holds the same role but didn't queue the proposal
+        testing_env!(get_context(bob()));
+        contract.gov_cancel(id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Governance actions need to be queued through `gov_queue`")]
+    fn gov_set_config_with_delay() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        contract.set_config(config());
+    }
+
+    #[test]
+    #[should_panic(expected = "Governance actions need to be queued through `gov_queue`")]
+    fn gov_grant_role_with_delay() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        contract.grant_role(Role::ConfigAdmin, bob());
+    }
+
+    #[test]
+    fn gov_queue_grant_role() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        let id = contract.gov_queue(
+            GovernanceAction::GrantRole(Role::ConfigAdmin, bob()),
+            U64(1000),
+        );
+
+        let mut ct = get_context(bob());
+        ct.block_timestamp = 1000;
+        testing_env!(ct);
+        contract.gov_execute(id);
+        assert!(contract.has_role(Role::ConfigAdmin, bob()));
+    }
+
+    #[test]
+    #[should_panic(expected = "This method is only callable by the governance contract gov.near")]
+    fn gov_queue_grant_role_non_gov() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        let id = contract.gov_queue(
+            GovernanceAction::GrantRole(Role::WhitelistManager, bob()),
+            U64(1000),
+        );
+
+        let mut ct = get_context(bob());
+        ct.block_timestamp = 1000;
+        testing_env!(ct);
+        contract.gov_execute(id);
+        // role members can't queue role changes
+        contract.gov_queue(
+            GovernanceAction::GrantRole(Role::ConfigAdmin, bob()),
+            U64(2000),
+        );
+    }
+
+    #[test]
+    fn gov_upgrade_proposal() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        let code = vec![1, 2, 3];
+        let id = contract.gov_queue(
+            GovernanceAction::Upgrade(Base64VecU8(env::sha256(&code))),
+            U64(1000),
+        );
+
+        let mut ct = get_context(bob());
+        ct.block_timestamp = 1000;
+        testing_env!(ct);
+        contract.assert_can_upgrade(&code);
+        assert_eq!(
+            contract.get_proposal(id).unwrap().status,
+            ProposalStatus::Executed
+        );
+    }
+}
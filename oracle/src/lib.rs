@@ -10,13 +10,20 @@ near_sdk::setup_alloc!();
 pub mod callback_args;
 pub mod data_request;
 pub mod fee_config;
+mod final_arbitrator;
 mod fungible_token_receiver;
+pub mod governance;
 mod helpers;
 mod logger;
 pub mod oracle_config;
+mod pause;
 mod requester_handler;
+mod requester_registration;
 mod resolution_window;
+pub mod roles;
 mod storage_manager;
+pub mod subscription;
+mod treasury;
 pub mod types;
 mod upgrade;
 pub mod whitelist;
@@ -24,10 +31,15 @@ pub mod whitelist;
 /// Mocks
 mod fungible_token;
 
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod test_utils;
+
 pub use callback_args::*;
 
 pub use data_request::{DataRequest, Source};
 pub use requester_handler::Requester;
+use roles::Role;
 use storage_manager::AccountStorageBalance;
 use types::*;
 
@@ -38,6 +50,15 @@ pub struct Contract {
     pub configs: Vector<oracle_config::OracleConfig>,
     pub data_requests: Vector<DataRequest>,
     pub accounts: LookupMap<AccountId, AccountStorageBalance>, // storage map
+    pub total_value_secured: Balance, // sum of the value secured by all whitelisted requesters
+    pub treasury_balances: LookupMap<AccountId, Balance>, // slashed stake accrued to the treasury per token
+    pub burned_balances: LookupMap<AccountId, Balance>, // slashed stake sent to the burn account per token
+    pub unburned_balances: LookupMap<AccountId, Balance>, // slashed stake whose transfer to the burn account failed per token
+    pub subscriptions: Vector<subscription::Subscription>,
+    pub proposals: Vector<governance::Proposal>,
+    pub roles: LookupMap<roles::Role, Vec<AccountId>>, // accounts holding each role next to the governance contract
+    pub requester_bonds: LookupMap<AccountId, requester_registration::RequesterBond>, // registration bonds of self-registered requesters
+    pub paused: bool, // wether new requests, subscriptions, registrations, stakes and commits are halted by a pauser
 }
 
 impl Default for Contract {
@@ -53,6 +74,21 @@ impl Contract {
         initial_whitelist: Option<Vec<Requester>>,
         config: oracle_config::OracleConfig,
     ) -> Self {
+        let total_value_secured: Balance = match &initial_whitelist {
+            Some(requesters) => requesters
+                .iter()
+                .map(|requester| requester.get_total_value_secured())
+                .sum(),
+            None => 0,
+        };
+
+        config.assert_valid_slashing_shares();
+        config.assert_valid_payment_tokens();
+        whitelist::assert_valid_stake_multiplier(config.registration_stake_multiplier);
+        config
+            .fee
+            .assert_valid_total_value_staked(total_value_secured);
+
         let mut configs = Vector::new(b"c".to_vec());
         configs.push(&config);
         logger::log_oracle_config(&config, 0);
@@ -62,6 +98,15 @@ impl Contract {
             configs,
             data_requests: Vector::new(b"dr".to_vec()),
             accounts: LookupMap::new(b"a".to_vec()),
+            total_value_secured,
+            treasury_balances: LookupMap::new(b"tb".to_vec()),
+            burned_balances: LookupMap::new(b"bb".to_vec()),
+            unburned_balances: LookupMap::new(b"ub".to_vec()),
+            subscriptions: Vector::new(b"sub".to_vec()),
+            proposals: Vector::new(b"gp".to_vec()),
+            roles: LookupMap::new(b"ro".to_vec()),
+            requester_bonds: LookupMap::new(b"rb".to_vec()),
+            paused: false,
         }
     }
 }
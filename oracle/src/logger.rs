@@ -6,8 +6,16 @@ use near_sdk::{
 };
 
 use crate::{
-    data_request::DataRequest, helpers::ns_to_ms, oracle_config::OracleConfig,
-    requester_handler::Requester, resolution_window::ResolutionWindow, types::*,
+    data_request::{DataRequest, DeliveryStatus},
+    governance::Proposal,
+    helpers::ns_to_ms,
+    oracle_config::OracleConfig,
+    requester_handler::Requester,
+    requester_registration::RequesterBond,
+    resolution_window::ResolutionWindow,
+    roles::Role,
+    subscription::Subscription,
+    types::*,
 };
 
 pub fn log_new_data_request(request: &DataRequest) {
@@ -27,6 +35,8 @@ pub fn log_new_data_request(request: &DataRequest) {
                 "initial_challenge_period": U64(request.initial_challenge_period),
                 "final_arbitrator_triggered": request.final_arbitrator_triggered,
                 "paid_fee": U128(request.request_config.paid_fee),
+                "expiry_time": U64(request.request_config.expiry_time),
+                "settlement_time": request.settlement_time.map(U64),
                 "stake_multiplier": request.request_config.stake_multiplier,
                 "global_config_id": U64(request.global_config_id),
                 "tags": request.tags,
@@ -34,6 +44,7 @@ pub fn log_new_data_request(request: &DataRequest) {
                 "block_height": U64(env::block_index()),
                 "data_type": request.data_type,
                 "creator": request.creator,
+                "payment_token": request.payment_token,
             }
         })
         .to_string()
@@ -55,6 +66,25 @@ pub fn log_update_data_request(request: &DataRequest) {
                 "finalized_outcome": request.finalized_outcome,
                 "initial_challenge_period": U64(request.initial_challenge_period),
                 "final_arbitrator_triggered": request.final_arbitrator_triggered,
+                "final_arbitrator_deadline": request.final_arbitrator_deadline.map(U64),
+                "delivery_status": request.delivery_status,
+                "cancelled": request.cancelled,
+            }
+        })
+        .to_string()
+        .as_bytes(),
+    );
+}
+
+pub fn log_outcome_delivery(request_id: u64, delivery_status: &DeliveryStatus) {
+    env::log(
+        json!({
+            "type": "data_requests",
+            "action": "update",
+            "cap_id": format!("dr_{}", request_id),
+            "params": {
+                "id": U64(request_id),
+                "delivery_status": delivery_status,
             }
         })
         .to_string()
@@ -74,16 +104,33 @@ pub fn log_oracle_config(config: &OracleConfig, id: u64) {
                 "final_arbitrator": config.final_arbitrator,
                 "stake_token": config.stake_token,
                 "payment_token": config.payment_token,
+                "payment_tokens": config.payment_tokens,
                 "validity_bond": config.validity_bond,
                 "max_outcomes": config.max_outcomes,
                 "default_challenge_window_duration": config.default_challenge_window_duration,
                 "min_initial_challenge_window_duration": config.min_initial_challenge_window_duration,
+                "request_expiry_duration": config.request_expiry_duration,
+                "max_settlement_duration": config.max_settlement_duration,
+                "forfeit_unrevealed_stakes": config.forfeit_unrevealed_stakes,
+                "treasury": config.treasury,
+                "treasury_share": config.treasury_share,
+                "burn_share": config.burn_share,
+                "burn_account": config.burn_account,
+                "invoke_final_arbitrator_contract": config.invoke_final_arbitrator_contract,
+                "final_arbitrator_deadline": config.final_arbitrator_deadline,
+                "final_arbitrator_fallback": config.final_arbitrator_fallback,
+                "unbonded_outcome_policy": config.unbonded_outcome_policy,
+                "registration_bond": config.registration_bond,
+                "registration_cooldown": config.registration_cooldown,
+                "registration_stake_multiplier": config.registration_stake_multiplier,
+                "governance_delay": config.governance_delay,
                 "final_arbitrator_invoke_amount": config.final_arbitrator_invoke_amount,
 
                 "fee": {
                     "flux_market_cap": config.fee.flux_market_cap,
                     "total_value_staked": config.fee.total_value_staked,
                     "resolution_fee_percentage": config.fee.resolution_fee_percentage,
+                    "min_resolution_fee": config.fee.min_resolution_fee,
                 },
 
                 "date": U64(ns_to_ms(env::block_timestamp())),
@@ -106,6 +153,7 @@ pub fn log_resolution_window(window: &ResolutionWindow) {
                 "dr_id": U64(window.dr_id),
                 "round": window.round,
                 "start_time": U64(window.start_time),
+                "commit_end_time": window.commit_end_time.map(U64),
                 "end_time": U64(window.end_time),
                 "bond_size": U128(window.bond_size),
                 "bonded_outcome": window.bonded_outcome,
@@ -129,6 +177,17 @@ fn outcome_to_id(outcome: &Outcome) -> String {
                 "ans_num_{}_{}_{}",
                 num_ans.value.0, num_ans.multiplier.0, num_ans.negative
             ),
+            AnswerType::Vector(vec_ans) => format!(
+                "ans_vec_{}",
+                vec_ans
+                    .iter()
+                    .map(|num_ans| format!(
+                        "{}_{}_{}",
+                        num_ans.value.0, num_ans.multiplier.0, num_ans.negative
+                    ))
+                    .collect::<Vec<String>>()
+                    .join("_")
+            ),
         },
         Outcome::Invalid => "invalid".to_string(),
     }
@@ -196,6 +255,7 @@ pub fn log_claim(
     user_correct_stake: u128,
     stake_profit: u128,
     fee_profit: u128,
+    refund: u128,
 ) {
     env::log(
         json!({
@@ -226,6 +286,132 @@ pub fn log_claim(
                 "user_correct_stake": U128(user_correct_stake),
                 "payout": U128(stake_profit),
                 "fee_profit": U128(fee_profit),
+                "refund": U128(refund),
+                "date": U64(ns_to_ms(env::block_timestamp())),
+                "block_height": U64(env::block_index()),
+            }
+        })
+        .to_string()
+        .as_bytes(),
+    );
+}
+
+pub fn log_treasury_accrual(
+    data_request_id: u64,
+    token: &AccountId,
+    treasury_amount: u128,
+    burn_amount: u128,
+) {
+    env::log(
+        json!({
+            "type": "treasury_accruals",
+            "action": "update",
+            "cap_id": format!("ta_{}", data_request_id),
+            "params": {
+                "id": format!("ta_{}", data_request_id),
+                "data_request_id": U64(data_request_id),
+                "token": token,
+                "treasury_amount": U128(treasury_amount),
+                "burn_amount": U128(burn_amount),
+                "date": U64(ns_to_ms(env::block_timestamp())),
+                "block_height": U64(env::block_index()),
+            }
+        })
+        .to_string()
+        .as_bytes(),
+    );
+}
+
+pub fn log_treasury_withdrawal(treasury: &AccountId, token: &AccountId, amount: u128) {
+    env::log(
+        json!({
+            "type": "treasury_withdrawals",
+            "params": {
+                "treasury": treasury,
+                "token": token,
+                "amount": U128(amount),
+                "date": U64(ns_to_ms(env::block_timestamp())),
+                "block_height": U64(env::block_index()),
+            }
+        })
+        .to_string()
+        .as_bytes(),
+    );
+}
+
+pub fn log_subscription(subscription: &Subscription) {
+    env::log(
+        json!({
+            "type": "subscriptions",
+            "action": "update",
+            "cap_id": format!("sub_{}", subscription.id),
+            "params": {
+                "id": U64(subscription.id),
+                "requester": subscription.requester,
+                "creator": subscription.creator,
+                "payment_token": subscription.payment_token,
+                "template": subscription.template,
+                "interval": U64(subscription.interval),
+                "remaining_instances": U64(subscription.remaining_instances),
+                "balance": U128(subscription.balance),
+                "last_request_id": subscription.last_request_id.map(U64),
+                "next_request_time": U64(subscription.next_request_time),
+                "cancelled": subscription.cancelled,
+                "date": U64(ns_to_ms(env::block_timestamp())),
+                "block_height": U64(env::block_index()),
+            }
+        })
+        .to_string()
+        .as_bytes(),
+    );
+}
+
+pub fn log_proposal(id: u64, proposal: &Proposal) {
+    env::log(
+        json!({
+            "type": "governance_proposals",
+            "action": "update",
+            "cap_id": format!("gp_{}", id),
+            "params": {
+                "id": U64(id),
+                "action": proposal.action,
+                "proposer": proposal.proposer,
+                "execution_time": proposal.execution_time,
+                "status": proposal.status,
+                "date": U64(ns_to_ms(env::block_timestamp())),
+                "block_height": U64(env::block_index()),
+            }
+        })
+        .to_string()
+        .as_bytes(),
+    );
+}
+
+pub fn log_role(role: Role, account_id: &AccountId, active: bool) {
+    env::log(
+        json!({
+            "type": "roles",
+            "action": "update",
+            "cap_id": format!("role_{:?}_{}", role, account_id),
+            "params": {
+                "role": role,
+                "account_id": account_id,
+                "active": active,
+                "date": U64(ns_to_ms(env::block_timestamp())),
+                "block_height": U64(env::block_index()),
+            }
+        })
+        .to_string()
+        .as_bytes(),
+    );
+}
+
+pub fn log_pause(paused: bool) {
+    env::log(
+        json!({
+            "type": "pause",
+            "params": {
+                "paused": paused,
                 "date": U64(ns_to_ms(env::block_timestamp())),
                 "block_height": U64(env::block_index()),
             }
@@ -247,6 +433,7 @@ pub fn log_whitelist(requester: &Requester, active: bool) {
                 "account_id": requester.account_id,
                 "stake_multiplier": requester.stake_multiplier,
                 "code_base_url": requester.code_base_url,
+                "total_value_secured": requester.total_value_secured,
                 "active": active,
                 "date": U64(ns_to_ms(env::block_timestamp())),
                 "block_height": U64(env::block_index()),
@@ -257,10 +444,74 @@ pub fn log_whitelist(requester: &Requester, active: bool) {
     );
 }
 
+pub fn log_whitelist_update(prev_requester: &Requester, requester: &Requester) {
+    env::log(
+        json!({
+            "type": "whitelist",
+            "action": "update",
+            "cap_id": format!("wl_{}", requester.account_id),
+            "params": {
+                "id": format!("wl_{}", requester.account_id),
+                "contract_name": requester.contract_name,
+                "account_id": requester.account_id,
+                "stake_multiplier": requester.stake_multiplier,
+                "code_base_url": requester.code_base_url,
+                "total_value_secured": requester.total_value_secured,
+                "active": true,
+                "previous": {
+                    "contract_name": prev_requester.contract_name,
+                    "stake_multiplier": prev_requester.stake_multiplier,
+                    "code_base_url": prev_requester.code_base_url,
+                },
+                "date": U64(ns_to_ms(env::block_timestamp())),
+                "block_height": U64(env::block_index()),
+            }
+        })
+        .to_string()
+        .as_bytes(),
+    );
+}
+
+#[derive(serde::Serialize)]
+pub enum RequesterBondAction {
+    Register,
+    Unregister,
+    Withdraw,
+    Slash,
+}
+
+pub fn log_requester_bond(
+    account_id: &AccountId,
+    bond: &RequesterBond,
+    action: RequesterBondAction,
+) {
+    env::log(
+        json!({
+            "type": "requester_bonds",
+            "action": "update",
+            "cap_id": format!("rb_{}", account_id),
+            "params": {
+                "id": format!("rb_{}", account_id),
+                "account_id": account_id,
+                "token": bond.token,
+                "amount": U128(bond.amount),
+                "unlock_time": bond.unlock_time.map(U64),
+                "type": action,
+                "date": U64(ns_to_ms(env::block_timestamp())),
+                "block_height": U64(env::block_index()),
+            }
+        })
+        .to_string()
+        .as_bytes(),
+    );
+}
+
 #[derive(serde::Serialize)]
 pub enum TransactionType {
     Stake,
     Unstake,
+    Commit,
+    WithdrawUnrevealed,
 }
 
 pub fn log_transaction(
@@ -310,6 +561,39 @@ pub fn log_stake_transaction(
     );
 }
 
+// Commits are logged without their outcome, it only becomes public once revealed through `log_stake_transaction`
+pub fn log_commit_transaction(
+    account_id: &AccountId,
+    window: &ResolutionWindow,
+    amount_in: Balance,
+) {
+    log_transaction(
+        TransactionType::Commit,
+        account_id,
+        window.dr_id,
+        Some(window.round),
+        amount_in,
+        0,
+        None,
+    );
+}
+
+pub fn log_withdraw_unrevealed_transaction(
+    account_id: &AccountId,
+    window: &ResolutionWindow,
+    amount_out: Balance,
+) {
+    log_transaction(
+        TransactionType::WithdrawUnrevealed,
+        account_id,
+        window.dr_id,
+        Some(window.round),
+        0,
+        amount_out,
+        None,
+    );
+}
+
 pub fn log_unstake_transaction(
     account_id: &AccountId,
     window: &ResolutionWindow,
@@ -4,17 +4,56 @@ use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::AccountId;
 
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum FinalArbitratorFallback {
+    LastBondedOutcome, // finalize with the outcome bonded in the round that invoked the final arbitrator
+    Invalid,           // finalize as `Invalid` and refund all stakes
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum UnbondedOutcomePolicy {
+    Refund, // return the stake of every round
+    Slash,  // slash the bonded stake of every round
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PaymentToken {
+    pub token: AccountId,
+    pub validity_bond: U128,  // Validity bond denominated in this token
+    pub fee_multiplier: U128, // The resolution fee, denominated in `payment_token`, is converted to this token as `fee * fee_multiplier / fee_divisor`
+    pub fee_divisor: U128,
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct OracleConfig {
     pub gov: AccountId,
     pub final_arbitrator: AccountId, // Invoked to have last say in `DataRequest`, this happens when the `challenge_bond` for a `DataRequest` is >= than `final_arbitrator_invoke_amount` / 100 % of the total supply
-    pub stake_token: AccountId,
+    pub stake_token: AccountId, // Token all requests are staked in, whichever token their fee and validity bond were paid in
     pub payment_token: AccountId,
+    pub payment_tokens: Vec<PaymentToken>, // Tokens other than `payment_token` that requesters are allowed to pay fees and validity bonds in
     pub validity_bond: U128,
     pub max_outcomes: u8,
     pub default_challenge_window_duration: WrappedTimestamp,
     pub min_initial_challenge_window_duration: WrappedTimestamp,
+    pub request_expiry_duration: WrappedTimestamp, // Duration after creation after which a `DataRequest` without a bonded outcome can be expired
+    pub max_settlement_duration: WrappedTimestamp, // Maximum duration between a request's creation and its `settlement_time`
+    pub forfeit_unrevealed_stakes: bool, // Wether committed stakes that were never revealed are kept by the oracle instead of refunded
+    pub treasury: AccountId, // Account that can withdraw the treasury's share of slashed stake
+    pub treasury_share: u16, // Share of incorrectly staked tokens that's routed to the treasury, denominated in 1e4 so 100 = 1%
+    pub burn_share: u16, // Share of incorrectly staked tokens that's burned, denominated in 1e4 so 100 = 1%
+    pub burn_account: AccountId, // Account nobody controls that the burned share is sent to, must be registered with the stake token
+    pub invoke_final_arbitrator_contract: bool, // Wether the final arbitrator is a contract implementing `FinalArbitratorContractExtern` that's called when invoked
+    pub final_arbitrator_deadline: WrappedTimestamp, // Duration after the final arbitrator is invoked after which anyone can trigger `final_arbitrator_fallback`
+    pub final_arbitrator_fallback: FinalArbitratorFallback,
+    pub unbonded_outcome_policy: UnbondedOutcomePolicy, // How rounds are settled when the final arbitrator chooses an outcome no round bonded
+    pub registration_bond: Option<U128>, // Amount of `stake_token` a requester locks to add itself to the whitelist, `None` disables self-registration
+    pub registration_cooldown: WrappedTimestamp, // Duration after unregistering after which a requester can withdraw its registration bond
+    pub registration_stake_multiplier: Option<u16>, // `stake_multiplier` of self-registered requesters
+    pub governance_delay: WrappedTimestamp, // Minimum delay between queueing a governance action and executing it, 0 lets governance apply actions immediately
     pub final_arbitrator_invoke_amount: U128, // Amount of tokens that, when bonded in a single `ResolutionWindow`, should trigger the final arbitrator
     pub fee: FeeConfig,
 }
@@ -27,14 +66,72 @@ impl Contract {
 
     #[payable]
     pub fn set_config(&mut self, new_config: OracleConfig) {
-        self.assert_gov();
+        self.assert_role_immediate(Role::ConfigAdmin);
 
         let initial_storage = env::storage_usage();
 
+        self.apply_config(new_config);
+
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+    }
+}
+
+impl Contract {
+    pub fn apply_config(&mut self, new_config: OracleConfig) {
+        new_config.assert_valid_slashing_shares();
+        new_config.assert_valid_payment_tokens();
+        whitelist::assert_valid_stake_multiplier(new_config.registration_stake_multiplier);
+
         self.configs.push(&new_config);
 
         logger::log_oracle_config(&new_config, self.configs.len() - 1);
-        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+    }
+}
+
+impl OracleConfig {
+    /**
+     * @notice Converts the payment for a new `DataRequest` to `token`, panics if requesters can't pay in `token`
+     * @param fee the resolution fee denominated in `payment_token`
+     * @returns the validity bond and resolution fee denominated in `token`
+     */
+    pub fn calc_payment(&self, token: &AccountId, fee: Balance) -> (Balance, Balance) {
+        if token == &self.payment_token {
+            return (self.validity_bond.into(), fee);
+        }
+
+        let payment_token = self
+            .payment_tokens
+            .iter()
+            .find(|payment_token| &payment_token.token == token)
+            .unwrap_or_else(|| panic!("Unsupported payment token {}", token));
+
+        (
+            payment_token.validity_bond.into(),
+            helpers::calc_product(
+                fee,
+                payment_token.fee_multiplier.into(),
+                payment_token.fee_divisor.into(),
+            ),
+        )
+    }
+
+    pub fn assert_valid_payment_tokens(&self) {
+        for payment_token in self.payment_tokens.iter() {
+            assert!(
+                u128::from(payment_token.fee_divisor) > 0,
+                "Fee divisor of {} can't be 0",
+                payment_token.token
+            );
+        }
+    }
+
+    pub fn assert_valid_slashing_shares(&self) {
+        assert!(
+            u32::from(self.treasury_share) + u32::from(self.burn_share)
+                <= u32::from(data_request::PERCENTAGE_DIVISOR),
+            "Treasury and burn share can't exceed {}",
+            data_request::PERCENTAGE_DIVISOR
+        );
     }
 }
 
@@ -76,19 +173,13 @@ mod mock_token_basic_tests {
     fn config(gov: AccountId) -> oracle_config::OracleConfig {
         oracle_config::OracleConfig {
             gov,
-            final_arbitrator: alice(),
-            payment_token: token(),
-            stake_token: token(),
             validity_bond: U128(0),
-            max_outcomes: 8,
-            default_challenge_window_duration: U64(1000),
-            min_initial_challenge_window_duration: U64(1000),
             final_arbitrator_invoke_amount: U128(25_000_000_000_000_000_000_000_000_000_000),
             fee: FeeConfig {
-                flux_market_cap: U128(50000),
-                total_value_staked: U128(10000),
                 resolution_fee_percentage: 5000, // 5%
+                ..test_utils::config().fee
             },
+            ..test_utils::config()
         }
     }
 
@@ -128,4 +219,15 @@ mod mock_token_basic_tests {
         let mut contract = Contract::new(None, config(gov()));
         contract.set_config(config(alice()));
     }
+
+    #[test]
+    #[should_panic(expected = "stake multiplier can't be 0")]
+    fn fail_set_config_zero_registration_stake_multiplier() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config(gov()));
+        contract.set_config(oracle_config::OracleConfig {
+            registration_stake_multiplier: Some(0),
+            ..config(gov())
+        });
+    }
 }
@@ -0,0 +1,129 @@
+use crate::*;
+
+#[near_bindgen]
+impl Contract {
+    /**
+     * @notice Stops new requests, subscriptions, registrations, stakes and commits until unpaused, only callable by the governance contract or pausers
+     */
+    pub fn pause(&mut self) {
+        self.assert_role(Role::Pauser);
+        assert!(!self.paused, "Oracle is already paused");
+        self.paused = true;
+
+        logger::log_pause(true);
+    }
+
+    /**
+     * @notice Lifts a pause, only callable by the governance contract or pausers
+     */
+    pub fn unpause(&mut self) {
+        self.assert_role(Role::Pauser);
+        assert!(self.paused, "Oracle is not paused");
+        self.paused = false;
+
+        logger::log_pause(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+impl Contract {
+    pub fn assert_not_paused(&self) {
+        assert!(!self.paused, "Oracle is paused");
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod mock_token_basic_tests {
+    use super::*;
+    use crate::test_utils::config;
+    use crate::types::{AnswerType, Outcome};
+    use near_sdk::MockedBlockchain;
+    use near_sdk::{testing_env, VMContext};
+
+    fn alice() -> AccountId {
+        "alice.near".to_string()
+    }
+
+    fn bob() -> AccountId {
+        "bob.near".to_string()
+    }
+
+    fn gov() -> AccountId {
+        "gov.near".to_string()
+    }
+
+    fn get_context(predecessor_account_id: AccountId) -> VMContext {
+        VMContext {
+            current_account_id: alice(),
+            signer_account_id: alice(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id,
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 10000 * 10u128.pow(24),
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 1000 * 10u128.pow(24),
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        }
+    }
+
+    #[test]
+    fn pauser_can_pause_and_unpause() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        contract.grant_role(Role::Pauser, bob());
+
+        testing_env!(get_context(bob()));
+        contract.pause();
+        assert!(contract.is_paused());
+
+        contract.unpause();
+        assert!(!contract.is_paused());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "This method is only callable by the governance contract gov.near or accounts with the Pauser role"
+    )]
+    fn pause_non_pauser() {
+        testing_env!(get_context(bob()));
+        let mut contract = Contract::new(None, config());
+        contract.pause();
+    }
+
+    #[test]
+    #[should_panic(expected = "Oracle is paused")]
+    fn paused_assert_not_paused() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        contract.pause();
+        contract.assert_not_paused();
+    }
+
+    #[test]
+    #[should_panic(expected = "Oracle is paused")]
+    fn dr_stake_paused() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        contract.pause();
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+    }
+}
@@ -3,6 +3,7 @@ use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{ext_contract, Gas, Promise, PromiseOrValue};
 
 const GAS_BASE_SET_OUTCOME: Gas = 250_000_000_000_000;
+pub const GAS_SET_OUTCOME: Gas = GAS_BASE_SET_OUTCOME / 10;
 
 #[ext_contract]
 pub trait RequesterContractExtern {
@@ -12,6 +13,7 @@ pub trait RequesterContractExtern {
         tags: Vec<String>,
         final_arbitrator_triggered: bool,
     );
+    fn set_cancelled(requester: AccountId, tags: Vec<String>);
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
@@ -20,6 +22,7 @@ pub struct Requester {
     pub account_id: AccountId, // Change to account_id
     pub stake_multiplier: Option<u16>,
     pub code_base_url: Option<String>,
+    pub total_value_secured: Option<WrappedBalance>, // value secured by the requester denominated in payment_token, used to calculate its resolution fee
 }
 
 #[ext_contract(ext_self)]
@@ -34,8 +37,13 @@ impl Requester {
             account_id: account_id.to_string(),
             stake_multiplier: None,
             code_base_url: None,
+            total_value_secured: None,
         }
     }
+    pub fn get_total_value_secured(&self) -> Balance {
+        self.total_value_secured.map(u128::from).unwrap_or(0)
+    }
+
     pub fn set_outcome(
         &self,
         outcome: Outcome,
@@ -50,7 +58,19 @@ impl Requester {
             // NEAR params
             &self.account_id,
             1,
-            GAS_BASE_SET_OUTCOME / 10,
+            GAS_SET_OUTCOME,
+        )
+    }
+
+    // @notice Notifies the requester that the `DataRequest` with `tags` was cancelled and won't get an outcome
+    pub fn set_cancelled(&self, tags: Vec<String>) -> Promise {
+        requester_contract_extern::set_cancelled(
+            self.account_id.to_string(),
+            tags,
+            // NEAR params
+            &self.account_id,
+            1,
+            GAS_SET_OUTCOME,
         )
     }
 }
@@ -0,0 +1,348 @@
+use crate::*;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{AccountId, Balance, Promise};
+
+use crate::fungible_token::fungible_token_transfer;
+use crate::logger::RequesterBondAction;
+
+#[derive(Serialize, Deserialize)]
+pub struct RegisterRequesterArgs {
+    pub contract_name: String,
+    pub code_base_url: Option<String>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct RequesterBond {
+    pub token: AccountId, // stake token the bond was locked in
+    pub amount: Balance,
+    pub unlock_time: Option<Timestamp>, // set when the requester unregisters, the bond can be withdrawn after it
+}
+
+#[near_bindgen]
+impl Contract {
+    /**
+     * @notice Adds `sender` to the whitelist in exchange for locking the configured registration bond
+     * @returns amount of tokens paid on top of the registration bond
+     */
+    pub fn requester_register(
+        &mut self,
+        sender: AccountId,
+        amount: Balance,
+        payload: RegisterRequesterArgs,
+    ) -> Balance {
+        self.assert_not_paused();
+        let config = self.get_config();
+        let registration_bond: Balance = config
+            .registration_bond
+            .expect("Requester registration is disabled")
+            .into();
+        self.assert_sender(&config.stake_token);
+        assert!(
+            self.whitelist.is_initiated(),
+            "Requesters don't need to register without a whitelist"
+        );
+        assert!(
+            !self.whitelist.contains(sender.to_string())
+                && self.requester_bonds.get(&sender).is_none(),
+            "{} is already a requester",
+            sender
+        );
+        assert!(
+            amount >= registration_bond,
+            "Registration bond of {} not reached, received only {}",
+            registration_bond,
+            amount
+        );
+
+        let requester = Requester {
+            contract_name: payload.contract_name,
+            account_id: sender.to_string(),
+            stake_multiplier: config.registration_stake_multiplier,
+            code_base_url: payload.code_base_url,
+            total_value_secured: None,
+        };
+        self.whitelist.insert(&requester);
+        let bond = RequesterBond {
+            token: config.stake_token,
+            amount: registration_bond,
+            unlock_time: None,
+        };
+        self.requester_bonds.insert(&sender, &bond);
+
+        logger::log_whitelist(&requester, true);
+        logger::log_requester_bond(&sender, &bond, RequesterBondAction::Register);
+
+        // refund anything paid on top of the registration bond
+        amount - registration_bond
+    }
+
+    /**
+     * @notice Removes the self-registered requester from the whitelist, its bond can be withdrawn after the registration cooldown
+     */
+    pub fn requester_unregister(&mut self) {
+        let requester_id = env::predecessor_account_id();
+        let mut bond = self
+            .requester_bonds
+            .get(&requester_id)
+            .expect("Not a registered requester");
+        assert!(
+            bond.unlock_time.is_none(),
+            "Requester is already unregistered"
+        );
+
+        let config = self.get_config();
+        bond.unlock_time = Some(env::block_timestamp() + u64::from(config.registration_cooldown));
+        self.requester_bonds.insert(&requester_id, &bond);
+
+        if let Some(requester) = self.whitelist.remove(&requester_id) {
+            self.update_total_value_secured(requester.get_total_value_secured(), 0);
+            logger::log_whitelist(&requester, false);
+        }
+        logger::log_requester_bond(&requester_id, &bond, RequesterBondAction::Unregister);
+    }
+
+    /**
+     * @notice Returns the registration bond of an unregistered requester once its cooldown has passed
+     */
+    pub fn requester_withdraw_bond(&mut self) -> Promise {
+        let requester_id = env::predecessor_account_id();
+        let bond = self
+            .requester_bonds
+            .get(&requester_id)
+            .expect("Not a registered requester");
+        let unlock_time = bond
+            .unlock_time
+            .expect("Requester needs to unregister before withdrawing its bond");
+        assert!(
+            env::block_timestamp() >= unlock_time,
+            "Registration bond can only be withdrawn after {}",
+            unlock_time
+        );
+
+        self.requester_bonds.remove(&requester_id);
+
+        logger::log_requester_bond(&requester_id, &bond, RequesterBondAction::Withdraw);
+        fungible_token_transfer(bond.token, requester_id, bond.amount)
+    }
+
+    /**
+     * @notice Slashes a self-registered requester's bond to the treasury and removes it from the whitelist
+     */
+    #[payable]
+    pub fn slash_requester_bond(&mut self, account_id: AccountId) {
+        self.assert_role_immediate(Role::WhitelistManager);
+
+        let initial_storage = env::storage_usage();
+
+        self.requester_bond_slash(account_id);
+
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+    }
+
+    pub fn get_requester_bond(&self, account_id: AccountId) -> Option<WrappedBalance> {
+        self.requester_bonds
+            .get(&account_id)
+            .map(|bond| U128(bond.amount))
+    }
+}
+
+impl Contract {
+    pub fn requester_bond_slash(&mut self, account_id: AccountId) {
+        let bond = self
+            .requester_bonds
+            .remove(&account_id)
+            .expect("Not a registered requester");
+        let balance = self.treasury_balances.get(&bond.token).unwrap_or(0);
+        self.treasury_balances
+            .insert(&bond.token, &(balance + bond.amount));
+
+        if let Some(requester) = self.whitelist.remove(&account_id) {
+            self.update_total_value_secured(requester.get_total_value_secured(), 0);
+            logger::log_whitelist(&requester, false);
+        }
+
+        logger::log_requester_bond(&account_id, &bond, RequesterBondAction::Slash);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod mock_token_basic_tests {
+    use super::*;
+    use near_sdk::MockedBlockchain;
+    use near_sdk::{testing_env, VMContext};
+
+    fn alice() -> AccountId {
+        "alice.near".to_string()
+    }
+
+    fn bob() -> AccountId {
+        "bob.near".to_string()
+    }
+
+    fn token() -> AccountId {
+        "token.near".to_string()
+    }
+
+    fn gov() -> AccountId {
+        "gov.near".to_string()
+    }
+
+    fn registry_entry(account: AccountId) -> Requester {
+        Requester {
+            contract_name: account.clone(),
+            account_id: account.clone(),
+            stake_multiplier: None,
+            code_base_url: None,
+            total_value_secured: None,
+        }
+    }
+
+    fn config() -> oracle_config::OracleConfig {
+        oracle_config::OracleConfig {
+            registration_bond: Some(U128(1000)),
+            registration_stake_multiplier: Some(20000),
+            ..test_utils::config()
+        }
+    }
+
+    fn get_context(predecessor_account_id: AccountId) -> VMContext {
+        VMContext {
+            current_account_id: alice(),
+            signer_account_id: alice(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id,
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 10000 * 10u128.pow(24),
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 1000 * 10u128.pow(24),
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        }
+    }
+
+    fn register_args() -> RegisterRequesterArgs {
+        RegisterRequesterArgs {
+            contract_name: "bob".to_string(),
+            code_base_url: None,
+        }
+    }
+
+    #[test]
+    fn requester_register_success() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(Some(vec![registry_entry(alice())]), config());
+
+        assert_eq!(
+            contract.requester_register(bob(), 1200, register_args()),
+            200
+        );
+        assert!(contract.whitelist.contains(bob()));
+        assert_eq!(contract.whitelist.get_stake_multiplier(&bob()), Some(20000));
+        assert_eq!(contract.get_requester_bond(bob()), Some(U128(1000)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Registration bond of 1000 not reached, received only 999")]
+    fn requester_register_bond_not_reached() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(Some(vec![registry_entry(alice())]), config());
+
+        contract.requester_register(bob(), 999, register_args());
+    }
+
+    #[test]
+    #[should_panic(expected = "Oracle is paused")]
+    fn requester_register_paused() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(Some(vec![registry_entry(alice())]), config());
+        contract.paused = true;
+
+        contract.requester_register(bob(), 1000, register_args());
+    }
+
+    #[test]
+    #[should_panic(expected = "alice.near is already a requester")]
+    fn requester_register_whitelisted() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(Some(vec![registry_entry(alice())]), config());
+
+        contract.requester_register(alice(), 1000, register_args());
+    }
+
+    #[test]
+    #[should_panic(expected = "Requester registration is disabled")]
+    fn requester_register_disabled() {
+        testing_env!(get_context(token()));
+        let mut c = config();
+        c.registration_bond = None;
+        let mut contract = Contract::new(Some(vec![registry_entry(alice())]), c);
+
+        contract.requester_register(bob(), 1000, register_args());
+    }
+
+    #[test]
+    fn requester_unregister_and_withdraw() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(Some(vec![registry_entry(alice())]), config());
+        contract.requester_register(bob(), 1000, register_args());
+
+        testing_env!(get_context(bob()));
+        contract.requester_unregister();
+        assert!(!contract.whitelist.contains(bob()));
+
+        let mut ct = get_context(bob());
+        ct.block_timestamp = 5000;
+        testing_env!(ct);
+        contract.requester_withdraw_bond();
+        assert_eq!(contract.get_requester_bond(bob()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Registration bond can only be withdrawn after 5000")]
+    fn requester_withdraw_bond_before_cooldown() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(Some(vec![registry_entry(alice())]), config());
+        contract.requester_register(bob(), 1000, register_args());
+
+        testing_env!(get_context(bob()));
+        contract.requester_unregister();
+
+        let mut ct = get_context(bob());
+        ct.block_timestamp = 4999;
+        testing_env!(ct);
+        contract.requester_withdraw_bond();
+    }
+
+    #[test]
+    fn slash_requester_bond_success() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(Some(vec![registry_entry(alice())]), config());
+        contract.requester_register(bob(), 1000, register_args());
+
+        testing_env!(get_context(gov()));
+        contract.slash_requester_bond(bob());
+        assert!(!contract.whitelist.contains(bob()));
+        assert_eq!(contract.get_requester_bond(bob()), None);
+        assert_eq!(contract.get_treasury_balance(token()), U128(1000));
+    }
+
+    #[test]
+    #[should_panic(expected = "This method is only callable by the governance contract gov.near")]
+    fn slash_requester_bond_non_gov() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(Some(vec![registry_entry(alice())]), config());
+        contract.requester_register(bob(), 1000, register_args());
+
+        testing_env!(get_context(bob()));
+        contract.slash_requester_bond(bob());
+    }
+}
@@ -1,8 +1,10 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::LookupMap;
+use near_sdk::collections::{LookupMap, UnorderedMap, Vector};
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::{AccountId, Balance};
+use near_sdk::serde_json;
+use near_sdk::{env, AccountId, Balance};
 
+use crate::data_request::DataRequestDataType;
 use crate::logger;
 use crate::types::*;
 
@@ -17,15 +19,44 @@ pub struct CorrectStake {
     pub user_stake: Balance,
 }
 
+// @returns sha256 of `account_id`, `dr_id` and `round` separated by colons, followed by a colon, the json serialized `outcome` and `salt`,
+// which is what stakers commit to. Binding it to the staker and round keeps others from copying a commitment and revealing it once published
+pub fn commitment_hash(
+    account_id: &AccountId,
+    dr_id: u64,
+    round: u16,
+    outcome: &Outcome,
+    salt: &str,
+) -> Vec<u8> {
+    let preimage = format!(
+        "{}:{}:{}:{}{}",
+        account_id,
+        dr_id,
+        round,
+        serde_json::to_string(outcome).unwrap(),
+        salt
+    );
+    env::sha256(preimage.as_bytes())
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Commitment {
+    pub hash: Vec<u8>,
+    pub amount: Balance,
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct ResolutionWindow {
     pub dr_id: u64,
     pub round: u16,
     pub start_time: Timestamp,
+    pub commit_end_time: Option<Timestamp>, // end of the commit phase and start of the reveal phase when using commit-reveal
     pub end_time: Timestamp,
     pub bond_size: Balance,
     pub outcome_to_stake: LookupMap<Outcome, Balance>,
-    pub user_to_outcome_to_stake: LookupMap<AccountId, LookupMap<Outcome, Balance>>,
+    pub staked_outcomes: Vector<Outcome>, // keys of `outcome_to_stake`, none of them are within the tolerance of each other
+    pub user_to_outcome_to_stake: LookupMap<AccountId, UnorderedMap<Outcome, Balance>>,
+    pub user_to_commitment: LookupMap<AccountId, Commitment>,
     pub bonded_outcome: Option<Outcome>,
 }
 
@@ -33,6 +64,7 @@ pub struct ResolutionWindow {
 pub struct ResolutionWindowSummary {
     pub round: u16,
     pub start_time: WrappedTimestamp,
+    pub commit_end_time: Option<WrappedTimestamp>,
     pub end_time: WrappedTimestamp,
     pub bond_size: WrappedBalance,
     pub bonded_outcome: Option<Outcome>,
@@ -44,18 +76,26 @@ impl ResolutionWindow {
         round: u16,
         prev_bond: Balance,
         challenge_period: u64,
+        reveal_period: Option<u64>,
         start_time: u64,
     ) -> Self {
+        // When using commit-reveal the challenge period is used for commits, followed by the reveal period
+        let commit_end_time = reveal_period.map(|_| start_time + challenge_period);
         let new_resolution_window = Self {
             dr_id,
             round,
             start_time,
-            end_time: start_time + challenge_period,
+            commit_end_time,
+            end_time: start_time + challenge_period + reveal_period.unwrap_or(0),
             bond_size: prev_bond * 2,
             outcome_to_stake: LookupMap::new(format!("ots{}:{}", dr_id, round).as_bytes().to_vec()),
+            staked_outcomes: Vector::new(format!("so{}:{}", dr_id, round).as_bytes().to_vec()),
             user_to_outcome_to_stake: LookupMap::new(
                 format!("utots{}:{}", dr_id, round).as_bytes().to_vec(),
             ),
+            user_to_commitment: LookupMap::new(
+                format!("utc{}:{}", dr_id, round).as_bytes().to_vec(),
+            ),
             bonded_outcome: None,
         };
 
@@ -63,13 +103,32 @@ impl ResolutionWindow {
         return new_resolution_window;
     }
 
+    // @returns the outcome already staked on in this window that `outcome` is within the tolerance of, so both are staked as one outcome
+    pub fn canonical_outcome(&self, outcome: Outcome, data_type: &DataRequestDataType) -> Outcome {
+        self.staked_outcomes
+            .iter()
+            .find(|staked_outcome| data_type.outcomes_match(staked_outcome, &outcome))
+            .unwrap_or(outcome)
+    }
+
     // @returns amount to refund users because it was not staked
     pub fn stake(&mut self, sender: AccountId, outcome: Outcome, amount: Balance) -> Balance {
-        let stake_on_outcome = self.outcome_to_stake.get(&outcome).unwrap_or(0);
+        // A bonded round only stays open for reveals of earlier commitments, those can no longer change its outcome
+        if self.bonded_outcome.is_some() {
+            return amount;
+        }
+
+        let stake_on_outcome = match self.outcome_to_stake.get(&outcome) {
+            Some(stake) => stake,
+            None => {
+                self.staked_outcomes.push(&outcome);
+                0
+            }
+        };
         let mut user_to_outcomes =
             self.user_to_outcome_to_stake
                 .get(&sender)
-                .unwrap_or(LookupMap::new(
+                .unwrap_or(UnorderedMap::new(
                     format!("utots:{}:{}:{}", self.dr_id, self.round, sender)
                         .as_bytes()
                         .to_vec(),
@@ -113,6 +172,64 @@ impl ResolutionWindow {
         unspent
     }
 
+    pub fn commit(&mut self, sender: AccountId, hash: Vec<u8>, amount: Balance) {
+        let now = env::block_timestamp();
+        assert!(
+            now >= self.start_time,
+            "Commit phase starts at {}",
+            self.start_time
+        );
+        assert!(
+            now < self
+                .commit_end_time
+                .expect("Round does not use commit-reveal"),
+            "Commit phase has ended"
+        );
+        assert!(
+            self.user_to_commitment.get(&sender).is_none(),
+            "{} already committed in this round",
+            sender
+        );
+
+        self.user_to_commitment
+            .insert(&sender, &Commitment { hash, amount });
+        logger::log_commit_transaction(&sender, &self, amount);
+    }
+
+    // @returns the committed amount after checking it matches `outcome` and `salt`, the caller stakes it
+    pub fn reveal(&mut self, sender: &AccountId, outcome: &Outcome, salt: &str) -> Balance {
+        let now = env::block_timestamp();
+        assert!(
+            now >= self
+                .commit_end_time
+                .expect("Round does not use commit-reveal")
+                && now < self.end_time,
+            "Can only reveal during the reveal phase"
+        );
+        let commitment = self
+            .user_to_commitment
+            .remove(sender)
+            .expect("No commitment found for this round");
+        assert_eq!(
+            commitment.hash,
+            commitment_hash(sender, self.dr_id, self.round, outcome, salt),
+            "Revealed outcome and salt do not match commitment"
+        );
+
+        commitment.amount
+    }
+
+    // @returns the committed amount of a commitment that can no longer be revealed
+    pub fn withdraw_unrevealed(&mut self, sender: &AccountId) -> Balance {
+        let commitment = self
+            .user_to_commitment
+            .remove(sender)
+            .expect("No commitment found for this round");
+        logger::log_withdraw_unrevealed_transaction(sender, &self, commitment.amount);
+
+        commitment.amount
+    }
+
     // @returns amount to refund users because it was not staked
     pub fn unstake(&mut self, sender: AccountId, outcome: Outcome, amount: Balance) -> Balance {
         assert!(
@@ -122,7 +239,7 @@ impl ResolutionWindow {
         let mut user_to_outcomes =
             self.user_to_outcome_to_stake
                 .get(&sender)
-                .unwrap_or(LookupMap::new(
+                .unwrap_or(UnorderedMap::new(
                     format!("utots:{}:{}:{}", self.dr_id, self.round, sender)
                         .as_bytes()
                         .to_vec(),
@@ -158,22 +275,100 @@ impl ResolutionWindow {
         amount
     }
 
+    // @returns the account's stake on outcomes other than the bonded outcome, which never took part in resolution
+    pub fn unbonded_stake_for(&self, account_id: &AccountId) -> Balance {
+        match self.user_to_outcome_to_stake.get(account_id) {
+            Some(outcome_to_stake) => outcome_to_stake
+                .iter()
+                .filter(|(outcome, _)| Some(outcome) != self.bonded_outcome.as_ref())
+                .map(|(_, stake)| stake)
+                .sum(),
+            None => 0,
+        }
+    }
+
+    // @returns the account's stake on `outcome` in this window
+    pub fn stake_on_outcome_for(&self, account_id: &AccountId, outcome: &Outcome) -> Balance {
+        match self.user_to_outcome_to_stake.get(account_id) {
+            Some(outcome_to_stake) => outcome_to_stake.get(outcome).unwrap_or(0),
+            None => 0,
+        }
+    }
+
+    // @returns the account's stake on all outcomes in this window
+    pub fn total_stake_for(&self, account_id: &AccountId) -> Balance {
+        match self.user_to_outcome_to_stake.get(account_id) {
+            Some(outcome_to_stake) => outcome_to_stake.values().sum(),
+            None => 0,
+        }
+    }
+
+    // @returns the account's stake on all outcomes in this window, removing it so it's only refunded once
+    pub fn claim_all_for(&mut self, account_id: &AccountId) -> Balance {
+        let total_stake = self.total_stake_for(account_id);
+        if let Some(mut outcome_to_stake) = self.user_to_outcome_to_stake.get(account_id) {
+            outcome_to_stake.clear();
+            self.user_to_outcome_to_stake
+                .insert(account_id, &outcome_to_stake);
+        }
+
+        total_stake
+    }
+
+    // @returns the account's unbonded stake, removing it so it's only refunded once
+    pub fn claim_unbonded_for(&mut self, account_id: &AccountId) -> Balance {
+        let unbonded_stake = self.unbonded_stake_for(account_id);
+        if let Some(mut outcome_to_stake) = self.user_to_outcome_to_stake.get(account_id) {
+            let unbonded_outcomes: Vec<Outcome> = outcome_to_stake
+                .keys()
+                .filter(|outcome| Some(outcome) != self.bonded_outcome.as_ref())
+                .collect();
+            for outcome in unbonded_outcomes {
+                outcome_to_stake.remove(&outcome);
+            }
+            self.user_to_outcome_to_stake
+                .insert(account_id, &outcome_to_stake);
+        }
+
+        unbonded_stake
+    }
+
+    // @returns the account's stake result for this window, removing its correct stake so it can't be claimed twice
     pub fn claim_for(
         &mut self,
         account_id: AccountId,
-        final_outcome: &Outcome,
+        correct_outcome: &Option<Outcome>,
+    ) -> WindowStakeResult {
+        let stake_result = self.stake_result_for(&account_id, correct_outcome);
+        if let WindowStakeResult::Correct(_) = stake_result {
+            if let Some(mut outcome_to_stake) = self.user_to_outcome_to_stake.get(&account_id) {
+                outcome_to_stake.remove(self.bonded_outcome.as_ref().unwrap());
+                self.user_to_outcome_to_stake
+                    .insert(&account_id, &outcome_to_stake);
+            }
+        }
+
+        stake_result
+    }
+
+    // @returns the account's stake result for this window without changing state
+    // @param correct_outcome the bonded outcome rounds are paid out as correct for, see `get_correct_bonded_outcome`
+    pub fn stake_result_for(
+        &self,
+        account_id: &AccountId,
+        correct_outcome: &Option<Outcome>,
     ) -> WindowStakeResult {
         // Check if there is a bonded outcome, if there is none it means it can be ignored in payout calc since it can only be the final unsuccessful window
         match &self.bonded_outcome {
             Some(bonded_outcome) => {
-                // If the bonded outcome for this window is equal to the finalized outcome the user's stake in this window and the total amount staked should be returned (which == `self.bond_size`)
-                if bonded_outcome == final_outcome {
+                // If the bonded outcome for this window is the correct outcome the user's stake in this window and the total amount staked should be returned (which == `self.bond_size`)
+                if correct_outcome.as_ref() == Some(bonded_outcome) {
                     WindowStakeResult::Correct(CorrectStake {
                         bonded_stake: self.bond_size,
                         // Get the users stake in this outcome for this window
-                        user_stake: match &mut self.user_to_outcome_to_stake.get(&account_id) {
+                        user_stake: match self.user_to_outcome_to_stake.get(account_id) {
                             Some(outcome_to_stake) => {
-                                outcome_to_stake.remove(&bonded_outcome).unwrap_or(0)
+                                outcome_to_stake.get(&bonded_outcome).unwrap_or(0)
                             }
                             None => 0,
                         },
@@ -0,0 +1,217 @@
+use crate::*;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::AccountId;
+
+#[derive(
+    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, Debug, PartialEq,
+)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Role {
+    ConfigAdmin,      // can change the oracle config
+    WhitelistManager, // can add, remove and update requesters and slash registration bonds
+    FeeManager,       // can change the fee config
+    Upgrader,         // can upgrade the contract code
+    Pauser,           // can pause and unpause the oracle, see `pause`
+}
+
+#[near_bindgen]
+impl Contract {
+    /**
+     * @notice Grants `role` to `account_id`, only callable by the governance contract
+     */
+    #[payable]
+    pub fn grant_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_gov_immediate();
+
+        let initial_storage = env::storage_usage();
+
+        self.role_grant(role, account_id);
+
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+    }
+
+    /**
+     * @notice Revokes `role` from `account_id`, only callable by the governance contract
+     */
+    #[payable]
+    pub fn revoke_role(&mut self, role: Role, account_id: AccountId) {
+        self.assert_gov_immediate();
+
+        let initial_storage = env::storage_usage();
+
+        self.role_revoke(role, account_id);
+
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+    }
+
+    pub fn has_role(&self, role: Role, account_id: AccountId) -> bool {
+        self.roles
+            .get(&role)
+            .map(|members| members.contains(&account_id))
+            .unwrap_or(false)
+    }
+
+    pub fn get_role_members(&self, role: Role) -> Vec<AccountId> {
+        self.roles.get(&role).unwrap_or_default()
+    }
+}
+
+impl Contract {
+    pub fn role_grant(&mut self, role: Role, account_id: AccountId) {
+        let mut members = self.roles.get(&role).unwrap_or_default();
+        assert!(
+            !members.contains(&account_id),
+            "{} already has the {:?} role",
+            account_id,
+            role
+        );
+        members.push(account_id.to_string());
+        self.roles.insert(&role, &members);
+
+        logger::log_role(role, &account_id, true);
+    }
+
+    pub fn role_revoke(&mut self, role: Role, account_id: AccountId) {
+        let mut members = self.roles.get(&role).unwrap_or_default();
+        let index = members
+            .iter()
+            .position(|member| member == &account_id)
+            .unwrap_or_else(|| panic!("{} doesn't have the {:?} role", account_id, role));
+        members.swap_remove(index);
+        self.roles.insert(&role, &members);
+
+        logger::log_role(role, &account_id, false);
+    }
+
+    // @notice Checks the predecessor is the governance contract or holds `role`
+    pub fn assert_role(&self, role: Role) {
+        let config = self.get_config();
+        let sender = env::predecessor_account_id();
+        assert!(
+            config.gov == sender || self.has_role(role, sender),
+            "This method is only callable by the governance contract {} or accounts with the {:?} role",
+            config.gov,
+            role
+        );
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod mock_token_basic_tests {
+    use super::*;
+    use crate::test_utils::config;
+    use near_sdk::MockedBlockchain;
+    use near_sdk::{testing_env, VMContext};
+    use whitelist::WhitelistHandler;
+
+    fn alice() -> AccountId {
+        "alice.near".to_string()
+    }
+
+    fn bob() -> AccountId {
+        "bob.near".to_string()
+    }
+
+    fn carol() -> AccountId {
+        "carol.near".to_string()
+    }
+
+    fn gov() -> AccountId {
+        "gov.near".to_string()
+    }
+
+    fn registry_entry(account: AccountId) -> Requester {
+        Requester {
+            contract_name: account.clone(),
+            account_id: account.clone(),
+            stake_multiplier: None,
+            code_base_url: None,
+            total_value_secured: None,
+        }
+    }
+
+    fn get_context(predecessor_account_id: AccountId) -> VMContext {
+        VMContext {
+            current_account_id: alice(),
+            signer_account_id: alice(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id,
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 10000 * 10u128.pow(24),
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 1000 * 10u128.pow(24),
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        }
+    }
+
+    #[test]
+    fn grant_and_revoke_role() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        contract.grant_role(Role::FeeManager, bob());
+        contract.grant_role(Role::FeeManager, carol());
+        assert!(contract.has_role(Role::FeeManager, bob()));
+        assert!(!contract.has_role(Role::ConfigAdmin, bob()));
+        assert_eq!(
+            contract.get_role_members(Role::FeeManager),
+            vec![bob(), carol()]
+        );
+
+        contract.revoke_role(Role::FeeManager, bob());
+        assert!(!contract.has_role(Role::FeeManager, bob()));
+        assert_eq!(contract.get_role_members(Role::FeeManager), vec![carol()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "This method is only callable by the governance contract gov.near")]
+    fn grant_role_non_gov() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        contract.grant_role(Role::ConfigAdmin, bob());
+
+        testing_env!(get_context(bob()));
+        contract.grant_role(Role::ConfigAdmin, carol());
+    }
+
+    #[test]
+    #[should_panic(expected = "bob.near doesn't have the Upgrader role")]
+    fn revoke_missing_role() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        contract.revoke_role(Role::Upgrader, bob());
+    }
+
+    #[test]
+    fn role_member_can_call_guarded_method() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(Some(vec![registry_entry(alice())]), config());
+        contract.grant_role(Role::WhitelistManager, bob());
+
+        testing_env!(get_context(bob()));
+        contract.add_to_whitelist(registry_entry(carol()));
+        assert!(contract.whitelist.contains(carol()));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "This method is only callable by the governance contract gov.near or accounts with the ConfigAdmin role"
+    )]
+    fn role_member_can_not_call_other_role_method() {
+        testing_env!(get_context(gov()));
+        let mut contract = Contract::new(None, config());
+        contract.grant_role(Role::WhitelistManager, bob());
+
+        testing_env!(get_context(bob()));
+        contract.set_config(config());
+    }
+}
@@ -177,10 +177,6 @@ mod mock_token_basic_tests {
         "target.near".to_string()
     }
 
-    fn gov() -> AccountId {
-        "gov.near".to_string()
-    }
-
     fn to_valid(account: AccountId) -> ValidAccountId {
         account.try_into().expect("invalid account")
     }
@@ -191,25 +187,17 @@ mod mock_token_basic_tests {
             account_id: account.clone(),
             stake_multiplier: None,
             code_base_url: None,
+            total_value_secured: None,
         }
     }
 
     fn config() -> oracle_config::OracleConfig {
         oracle_config::OracleConfig {
-            gov: gov(),
-            final_arbitrator: alice(),
-            payment_token: token(),
-            stake_token: token(),
-            validity_bond: U128(100),
-            max_outcomes: 8,
-            default_challenge_window_duration: U64(1000),
-            min_initial_challenge_window_duration: U64(1000),
-            final_arbitrator_invoke_amount: U128(250),
             fee: FeeConfig {
-                flux_market_cap: U128(50000),
-                total_value_staked: U128(10000),
                 resolution_fee_percentage: 5000, // 5%
+                ..test_utils::config().fee
             },
+            ..test_utils::config()
         }
     }
 
@@ -0,0 +1,582 @@
+use crate::*;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{AccountId, Balance, PromiseOrValue};
+
+use crate::data_request::DataRequestDataType;
+use crate::fungible_token::fungible_token_transfer;
+
+// Fields of the `DataRequest`s created for a subscription
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone)]
+pub struct SubscriptionTemplate {
+    pub sources: Vec<data_request::Source>,
+    pub tags: Vec<String>,
+    pub description: Option<String>,
+    pub outcomes: Option<Vec<String>>,
+    pub challenge_period: WrappedTimestamp,
+    pub data_type: DataRequestDataType,
+    pub reveal_period: Option<WrappedTimestamp>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct NewSubscriptionArgs {
+    pub template: SubscriptionTemplate,
+    pub interval: WrappedTimestamp, // minimum time between two requests of the subscription
+    pub instances: u64,             // number of requests prepaid for
+    pub creator: AccountId,         // Account to return validity bonds and the unspent balance to
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TopUpSubscriptionArgs {
+    pub id: U64,
+    pub instances: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Subscription {
+    pub id: u64,
+    pub requester: AccountId,
+    pub creator: AccountId,
+    pub payment_token: AccountId,
+    pub template: SubscriptionTemplate,
+    pub interval: Duration,
+    pub remaining_instances: u64,
+    pub balance: Balance, // prepaid amount of `payment_token` the fee and validity bond of each request is deducted from
+    pub last_request_id: Option<u64>,
+    pub next_request_time: Timestamp, // time after which the next request can be created
+    pub cancelled: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SubscriptionSummary {
+    pub id: U64,
+    pub requester: AccountId,
+    pub creator: AccountId,
+    pub payment_token: AccountId,
+    pub template: SubscriptionTemplate,
+    pub interval: WrappedTimestamp,
+    pub remaining_instances: u64,
+    pub balance: WrappedBalance,
+    pub last_request_id: Option<U64>,
+    pub next_request_time: WrappedTimestamp,
+    pub cancelled: bool,
+}
+
+impl Subscription {
+    fn assert_requester_or_creator(&self) {
+        let sender = env::predecessor_account_id();
+        assert!(
+            sender == self.requester || sender == self.creator,
+            "Only the requester or creator can manage this subscription"
+        );
+    }
+
+    fn assert_active(&self) {
+        assert!(!self.cancelled, "Subscription is cancelled");
+    }
+
+    fn new_data_request_args(&self) -> NewDataRequestArgs {
+        NewDataRequestArgs {
+            sources: self.template.sources.clone(),
+            tags: self.template.tags.clone(),
+            description: self.template.description.clone(),
+            outcomes: self.template.outcomes.clone(),
+            challenge_period: self.template.challenge_period,
+            data_type: self.template.data_type.clone(),
+            creator: self.creator.clone(),
+            reveal_period: self.template.reveal_period,
+            settlement_time: None,
+        }
+    }
+
+    fn summarize(&self) -> SubscriptionSummary {
+        SubscriptionSummary {
+            id: U64(self.id),
+            requester: self.requester.clone(),
+            creator: self.creator.clone(),
+            payment_token: self.payment_token.clone(),
+            template: self.template.clone(),
+            interval: U64(self.interval),
+            remaining_instances: self.remaining_instances,
+            balance: U128(self.balance),
+            last_request_id: self.last_request_id.map(U64),
+            next_request_time: U64(self.next_request_time),
+            cancelled: self.cancelled,
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    /**
+     * @notice Creates a subscription that's prepaid for `instances` requests and creates its first request
+     * @returns amount of tokens paid on top of the prepaid requests
+     */
+    pub fn subscription_new(
+        &mut self,
+        sender: AccountId,
+        amount: Balance,
+        payload: NewSubscriptionArgs,
+    ) -> Balance {
+        self.assert_not_paused();
+        self.assert_whitelisted(sender.to_string());
+        assert!(
+            payload.instances > 0,
+            "Subscription needs at least one instance"
+        );
+        let interval: u64 = payload.interval.into();
+        assert!(
+            interval >= u64::from(payload.template.challenge_period),
+            "Interval can't be shorter than the challenge period"
+        );
+
+        let (validity_bond, fee) =
+            self.subscription_request_cost(&sender, &env::predecessor_account_id());
+        let cost = validity_bond + fee;
+        let prepaid = cost * u128::from(payload.instances);
+        assert!(
+            amount >= prepaid,
+            "Prepaid amount of {} doesn't cover {} requests, received only {}",
+            prepaid,
+            payload.instances,
+            amount
+        );
+
+        let mut subscription = Subscription {
+            id: self.subscriptions.len(),
+            requester: sender,
+            creator: payload.creator,
+            payment_token: env::predecessor_account_id(),
+            template: payload.template,
+            interval,
+            remaining_instances: payload.instances,
+            balance: prepaid,
+            last_request_id: None,
+            next_request_time: env::block_timestamp(),
+            cancelled: false,
+        };
+        self.dr_validate(&subscription.new_data_request_args());
+        self.subscriptions.push(&subscription);
+        self.subscription_spawn_request(&mut subscription);
+
+        // refund anything paid on top of the prepaid requests
+        amount - prepaid
+    }
+
+    /**
+     * @notice Adds `amount` to the subscription's balance and `instances` to its remaining requests, anything paid on top of
+     * the added requests is kept in the balance to cover fee increases
+     * @returns amount of tokens that weren't used
+     */
+    pub fn subscription_top_up(
+        &mut self,
+        sender: AccountId,
+        amount: Balance,
+        payload: TopUpSubscriptionArgs,
+    ) -> Balance {
+        let mut subscription = self.subscription_get_expect(payload.id);
+        subscription.assert_active();
+        self.assert_sender(&subscription.payment_token);
+        assert!(
+            sender == subscription.requester || sender == subscription.creator,
+            "Only the requester or creator can manage this subscription"
+        );
+        let (validity_bond, fee) =
+            self.subscription_request_cost(&subscription.requester, &subscription.payment_token);
+        let prepaid = (validity_bond + fee) * u128::from(payload.instances);
+        assert!(
+            amount >= prepaid,
+            "Prepaid amount of {} doesn't cover {} requests, received only {}",
+            prepaid,
+            payload.instances,
+            amount
+        );
+
+        subscription.balance += amount;
+        subscription.remaining_instances += payload.instances;
+        self.subscriptions.replace(subscription.id, &subscription);
+
+        logger::log_subscription(&subscription);
+
+        0
+    }
+
+    /**
+     * @notice Cancels the subscription, requests that were already created are resolved as usual
+     * @returns promise returning the unspent balance to the creator, if any
+     */
+    pub fn subscription_cancel(&mut self, subscription_id: U64) -> PromiseOrValue<bool> {
+        let mut subscription = self.subscription_get_expect(subscription_id);
+        subscription.assert_requester_or_creator();
+        subscription.assert_active();
+
+        let refund = subscription.balance;
+        subscription.cancelled = true;
+        subscription.remaining_instances = 0;
+        subscription.balance = 0;
+        self.subscriptions.replace(subscription.id, &subscription);
+
+        logger::log_subscription(&subscription);
+
+        if refund > 0 {
+            PromiseOrValue::Promise(fungible_token_transfer(
+                subscription.payment_token,
+                subscription.creator,
+                refund,
+            ))
+        } else {
+            PromiseOrValue::Value(false)
+        }
+    }
+
+    /**
+     * @notice Creates the subscription's next request once its interval has passed, callable by anyone so that keepers can
+     * spawn requests independently of the finalization of the previous one
+     * @returns the id of the created `DataRequest`
+     */
+    pub fn subscription_spawn(&mut self, subscription_id: U64) -> U64 {
+        self.assert_not_paused();
+        let mut subscription = self.subscription_get_expect(subscription_id);
+        subscription.assert_active();
+        assert!(
+            env::block_timestamp() >= subscription.next_request_time,
+            "Next request can only be created after {}",
+            subscription.next_request_time
+        );
+
+        U64(self.subscription_spawn_request(&mut subscription))
+    }
+
+    pub fn get_subscription(&self, subscription_id: U64) -> Option<SubscriptionSummary> {
+        self.subscriptions
+            .get(subscription_id.into())
+            .map(|subscription| subscription.summarize())
+    }
+}
+
+impl Contract {
+    fn subscription_get_expect(&self, id: U64) -> Subscription {
+        self.subscriptions
+            .get(id.into())
+            .expect("ERR_SUBSCRIPTION_NOT_FOUND")
+    }
+
+    // @returns the validity bond and resolution fee of a subscription's request, denominated in `payment_token`
+    fn subscription_request_cost(
+        &self,
+        requester_id: &AccountId,
+        payment_token: &AccountId,
+    ) -> (Balance, Balance) {
+        let config = self.get_config();
+        let requester = self.whitelist.whitelist_get_expect(requester_id);
+        config.calc_payment(
+            payment_token,
+            config.fee.calc_resolution_fee(
+                self.total_value_secured,
+                requester.get_total_value_secured(),
+            ),
+        )
+    }
+
+    /**
+     * @notice Creates a `DataRequest` from the subscription's template, paying its fee and validity bond from the balance
+     * @returns the id of the created `DataRequest`
+     */
+    fn subscription_spawn_request(&mut self, subscription: &mut Subscription) -> u64 {
+        assert!(
+            subscription.remaining_instances > 0,
+            "Subscription has no prepaid requests left"
+        );
+        let (validity_bond, paid_fee) =
+            self.subscription_request_cost(&subscription.requester, &subscription.payment_token);
+        let cost = validity_bond + paid_fee;
+        assert!(
+            subscription.balance >= cost,
+            "Subscription balance of {} doesn't cover the request cost of {}, it needs to be topped up",
+            subscription.balance,
+            cost
+        );
+
+        let config = self.get_config();
+        let requester = self.whitelist.whitelist_get_expect(&subscription.requester);
+        let dr_id = self.dr_insert(
+            requester,
+            &config,
+            subscription.payment_token.clone(),
+            validity_bond,
+            paid_fee,
+            subscription.new_data_request_args(),
+            Some(subscription.id),
+        );
+
+        subscription.balance -= cost;
+        subscription.remaining_instances -= 1;
+        subscription.last_request_id = Some(dr_id);
+        subscription.next_request_time = env::block_timestamp() + subscription.interval;
+        self.subscriptions.replace(subscription.id, &subscription);
+
+        logger::log_subscription(&subscription);
+
+        dr_id
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod mock_token_basic_tests {
+    use super::*;
+    use crate::test_utils::config;
+    use near_sdk::MockedBlockchain;
+    use near_sdk::{testing_env, VMContext};
+
+    fn alice() -> AccountId {
+        "alice.near".to_string()
+    }
+
+    fn bob() -> AccountId {
+        "bob.near".to_string()
+    }
+
+    fn carol() -> AccountId {
+        "carol.near".to_string()
+    }
+
+    fn token() -> AccountId {
+        "token.near".to_string()
+    }
+
+    fn gov() -> AccountId {
+        "gov.near".to_string()
+    }
+
+    fn get_context(predecessor_account_id: AccountId) -> VMContext {
+        VMContext {
+            current_account_id: alice(),
+            signer_account_id: alice(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id,
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 10000 * 10u128.pow(24),
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        }
+    }
+
+    fn subscription_args(instances: u64) -> NewSubscriptionArgs {
+        NewSubscriptionArgs {
+            template: SubscriptionTemplate {
+                sources: Vec::new(),
+                tags: vec!["1".to_string()],
+                description: Some("a".to_string()),
+                outcomes: Some(vec!["a".to_string(), "b".to_string()]),
+                challenge_period: U64(1500),
+                data_type: DataRequestDataType::String,
+                reveal_period: None,
+            },
+            interval: U64(2000),
+            instances,
+            creator: bob(),
+        }
+    }
+
+    #[test]
+    fn subscription_new_spawns_first_request() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(None, config());
+
+        let refund = contract.subscription_new(bob(), 250, subscription_args(2));
+        assert_eq!(refund, 50);
+
+        let subscription = contract.get_subscription(U64(0)).unwrap();
+        assert_eq!(subscription.balance, U128(100));
+        assert_eq!(subscription.remaining_instances, 1);
+        assert_eq!(subscription.last_request_id, Some(U64(0)));
+        assert_eq!(subscription.next_request_time, U64(2000));
+        assert_eq!(
+            contract.data_requests.get(0).unwrap().subscription_id,
+            Some(0)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Prepaid amount of 200 doesn't cover 2 requests, received only 199")]
+    fn subscription_new_underpaid() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(None, config());
+
+        contract.subscription_new(bob(), 199, subscription_args(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Oracle is paused")]
+    fn subscription_new_paused() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(None, config());
+        contract.paused = true;
+
+        contract.subscription_new(bob(), 200, subscription_args(2));
+    }
+
+    #[test]
+    fn subscription_spawn_after_interval() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(None, config());
+        contract.subscription_new(bob(), 200, subscription_args(2));
+
+        let mut ct = get_context(carol());
+        ct.block_timestamp = 2000;
+        testing_env!(ct);
+        assert_eq!(contract.subscription_spawn(U64(0)), U64(1));
+
+        let subscription = contract.get_subscription(U64(0)).unwrap();
+        assert_eq!(subscription.balance, U128(0));
+        assert_eq!(subscription.remaining_instances, 0);
+        assert_eq!(subscription.next_request_time, U64(4000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Next request can only be created after 2000")]
+    fn subscription_spawn_before_interval() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(None, config());
+        contract.subscription_new(bob(), 200, subscription_args(2));
+
+        let mut ct = get_context(carol());
+        ct.block_timestamp = 1999;
+        testing_env!(ct);
+        contract.subscription_spawn(U64(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Subscription has no prepaid requests left")]
+    fn subscription_spawn_exhausted() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(None, config());
+        contract.subscription_new(bob(), 100, subscription_args(1));
+
+        let mut ct = get_context(carol());
+        ct.block_timestamp = 2000;
+        testing_env!(ct);
+        contract.subscription_spawn(U64(0));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Subscription balance of 100 doesn't cover the request cost of 150, it needs to be topped up"
+    )]
+    fn subscription_spawn_insufficient_balance() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(None, config());
+        contract.subscription_new(bob(), 200, subscription_args(2));
+
+        let mut new_config = config();
+        new_config.validity_bond = U128(150);
+        let mut ct = get_context(gov());
+        ct.attached_deposit = 10u128.pow(24);
+        testing_env!(ct);
+        contract.set_config(new_config);
+
+        let mut ct = get_context(carol());
+        ct.block_timestamp = 2000;
+        testing_env!(ct);
+        contract.subscription_spawn(U64(0));
+    }
+
+    #[test]
+    fn subscription_finalization_does_not_spawn() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(None, config());
+        contract.subscription_new(bob(), 200, subscription_args(2));
+
+        contract.dr_stake(
+            alice(),
+            200,
+            StakeDataRequestArgs {
+                id: U64(0),
+                outcome: data_request::Outcome::Answer(AnswerType::String("a".to_string())),
+            },
+        );
+
+        let mut ct = get_context(carol());
+        ct.block_timestamp = 2000;
+        testing_env!(ct);
+        contract.dr_finalize(U64(0));
+
+        // finalization never depends on the subscription, its next request is spawned separately
+        assert!(contract
+            .data_requests
+            .get(0)
+            .unwrap()
+            .finalized_outcome
+            .is_some());
+        assert_eq!(contract.data_requests.len(), 1);
+
+        assert_eq!(contract.subscription_spawn(U64(0)), U64(1));
+        assert_eq!(
+            contract.get_subscription(U64(0)).unwrap().last_request_id,
+            Some(U64(1))
+        );
+    }
+
+    #[test]
+    fn subscription_top_up_and_cancel() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(None, config());
+        contract.subscription_new(bob(), 100, subscription_args(1));
+
+        contract.subscription_top_up(
+            bob(),
+            300,
+            TopUpSubscriptionArgs {
+                id: U64(0),
+                instances: 3,
+            },
+        );
+        let subscription = contract.get_subscription(U64(0)).unwrap();
+        assert_eq!(subscription.balance, U128(300));
+        assert_eq!(subscription.remaining_instances, 3);
+
+        testing_env!(get_context(bob()));
+        contract.subscription_cancel(U64(0));
+        let subscription = contract.get_subscription(U64(0)).unwrap();
+        assert!(subscription.cancelled);
+        assert_eq!(subscription.balance, U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Prepaid amount of 300 doesn't cover 3 requests, received only 299")]
+    fn subscription_top_up_underpaid() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(None, config());
+        contract.subscription_new(bob(), 100, subscription_args(1));
+
+        contract.subscription_top_up(
+            bob(),
+            299,
+            TopUpSubscriptionArgs {
+                id: U64(0),
+                instances: 3,
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the requester or creator can manage this subscription")]
+    fn subscription_cancel_unauthorized() {
+        testing_env!(get_context(token()));
+        let mut contract = Contract::new(None, config());
+        contract.subscription_new(bob(), 100, subscription_args(1));
+
+        testing_env!(get_context(carol()));
+        contract.subscription_cancel(U64(0));
+    }
+}
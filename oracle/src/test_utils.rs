@@ -0,0 +1,40 @@
+use crate::*;
+use fee_config::FeeConfig;
+use oracle_config::{FinalArbitratorFallback, OracleConfig, UnbondedOutcomePolicy};
+
+// @returns the config unit tests start from, test modules override the fields their tests depend on
+pub fn config() -> OracleConfig {
+    OracleConfig {
+        gov: "gov.near".to_string(),
+        final_arbitrator: "alice.near".to_string(),
+        payment_token: "token.near".to_string(),
+        stake_token: "token.near".to_string(),
+        payment_tokens: vec![],
+        validity_bond: U128(100),
+        max_outcomes: 8,
+        default_challenge_window_duration: U64(1000),
+        min_initial_challenge_window_duration: U64(1000),
+        request_expiry_duration: U64(5000),
+        max_settlement_duration: U64(10000),
+        forfeit_unrevealed_stakes: false,
+        treasury: "treasury.near".to_string(),
+        treasury_share: 0,
+        burn_share: 0,
+        burn_account: "burn.near".to_string(),
+        invoke_final_arbitrator_contract: false,
+        final_arbitrator_deadline: U64(5000),
+        final_arbitrator_fallback: FinalArbitratorFallback::LastBondedOutcome,
+        unbonded_outcome_policy: UnbondedOutcomePolicy::Refund,
+        registration_bond: None,
+        registration_cooldown: U64(5000),
+        registration_stake_multiplier: None,
+        governance_delay: U64(0),
+        final_arbitrator_invoke_amount: U128(250),
+        fee: FeeConfig {
+            flux_market_cap: U128(50000),
+            total_value_staked: U128(10000),
+            resolution_fee_percentage: 10_000,
+            min_resolution_fee: U128(0),
+        },
+    }
+}
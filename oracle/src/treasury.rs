@@ -0,0 +1,263 @@
+use crate::*;
+use near_sdk::{ext_contract, Gas, Promise, PromiseResult};
+
+use crate::fungible_token::fungible_token_transfer;
+
+pub const GAS_RESOLVE_TRANSFER: Gas = 5_000_000_000_000;
+
+#[ext_contract]
+trait ExtSelf {
+    fn treasury_resolve_withdraw(token: AccountId, amount: U128);
+    fn treasury_resolve_burn(token: AccountId, amount: U128);
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_treasury_balance(&self, token: AccountId) -> U128 {
+        U128(self.treasury_balances.get(&token).unwrap_or(0))
+    }
+
+    pub fn get_burned_balance(&self, token: AccountId) -> U128 {
+        U128(self.burned_balances.get(&token).unwrap_or(0))
+    }
+
+    pub fn get_unburned_balance(&self, token: AccountId) -> U128 {
+        U128(self.unburned_balances.get(&token).unwrap_or(0))
+    }
+
+    /**
+     * @notice Transfers the accrued slashed stake of `token` to the treasury
+     */
+    pub fn treasury_withdraw(&mut self, token: AccountId) -> Promise {
+        let config = self.get_config();
+        assert_eq!(
+            config.treasury,
+            env::predecessor_account_id(),
+            "This method is only callable by the treasury {}",
+            config.treasury
+        );
+        let balance = self
+            .treasury_balances
+            .remove(&token)
+            .expect("No treasury balance for this token");
+
+        fungible_token_transfer(token.to_string(), config.treasury, balance).then(
+            ext_self::treasury_resolve_withdraw(
+                token,
+                U128(balance),
+                // NEAR params
+                &env::current_account_id(),
+                0,
+                GAS_RESOLVE_TRANSFER,
+            ),
+        )
+    }
+
+    /**
+     * @notice Retries sending slashed stake of `token` to the burn account after an earlier transfer failed
+     */
+    pub fn treasury_burn(&mut self, token: AccountId) -> Promise {
+        let amount = self
+            .unburned_balances
+            .remove(&token)
+            .expect("No unburned balance for this token");
+
+        self.burn(token, amount)
+    }
+
+    /**
+     * @notice Restores the treasury balance if the withdrawal transfer failed
+     */
+    #[private]
+    pub fn treasury_resolve_withdraw(&mut self, token: AccountId, amount: U128) {
+        self.resolve_withdraw(token, amount.into(), transfer_succeeded());
+    }
+
+    /**
+     * @notice Records the burned amount if the transfer to the burn account succeeded, otherwise keeps it for `treasury_burn`
+     */
+    #[private]
+    pub fn treasury_resolve_burn(&mut self, token: AccountId, amount: U128) {
+        self.resolve_burn(token, amount.into(), transfer_succeeded());
+    }
+}
+
+fn transfer_succeeded() -> bool {
+    assert_eq!(env::promise_results_count(), 1, "ERR_PROMISE_RESULTS_COUNT");
+    matches!(env::promise_result(0), PromiseResult::Successful(_))
+}
+
+impl Contract {
+    // @notice Adds the treasury's share of a `DataRequest`'s slashed stake to its balance and sends the burned share to the burn account
+    pub fn accrue_slashed_stake(
+        &mut self,
+        data_request_id: u64,
+        token: AccountId,
+        treasury_amount: Balance,
+        burn_amount: Balance,
+    ) {
+        if treasury_amount + burn_amount == 0 {
+            return;
+        }
+
+        if treasury_amount > 0 {
+            let balance = self.treasury_balances.get(&token).unwrap_or(0);
+            self.treasury_balances
+                .insert(&token, &(balance + treasury_amount));
+        }
+        if burn_amount > 0 {
+            self.burn(token.to_string(), burn_amount);
+        }
+        logger::log_treasury_accrual(data_request_id, &token, treasury_amount, burn_amount);
+    }
+
+    fn burn(&self, token: AccountId, amount: Balance) -> Promise {
+        fungible_token_transfer(token.to_string(), self.get_config().burn_account, amount).then(
+            ext_self::treasury_resolve_burn(
+                token,
+                U128(amount),
+                // NEAR params
+                &env::current_account_id(),
+                0,
+                GAS_RESOLVE_TRANSFER,
+            ),
+        )
+    }
+
+    fn resolve_withdraw(&mut self, token: AccountId, amount: Balance, success: bool) {
+        if success {
+            logger::log_treasury_withdrawal(&self.get_config().treasury, &token, amount);
+        } else {
+            let balance = self.treasury_balances.get(&token).unwrap_or(0);
+            self.treasury_balances.insert(&token, &(balance + amount));
+        }
+    }
+
+    fn resolve_burn(&mut self, token: AccountId, amount: Balance, success: bool) {
+        let balances = if success {
+            &mut self.burned_balances
+        } else {
+            &mut self.unburned_balances
+        };
+        let balance = balances.get(&token).unwrap_or(0);
+        balances.insert(&token, &(balance + amount));
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod mock_token_basic_tests {
+    use super::*;
+    use near_sdk::MockedBlockchain;
+    use near_sdk::{testing_env, VMContext};
+
+    fn alice() -> AccountId {
+        "alice.near".to_string()
+    }
+
+    fn token() -> AccountId {
+        "token.near".to_string()
+    }
+
+    fn treasury() -> AccountId {
+        "treasury.near".to_string()
+    }
+
+    fn config() -> oracle_config::OracleConfig {
+        oracle_config::OracleConfig {
+            treasury: treasury(),
+            treasury_share: 1000,
+            burn_share: 500,
+            ..test_utils::config()
+        }
+    }
+
+    fn get_context(predecessor_account_id: AccountId) -> VMContext {
+        VMContext {
+            current_account_id: alice(),
+            signer_account_id: alice(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id,
+            input: vec![],
+            block_index: 0,
+            block_timestamp: 0,
+            account_balance: 10000 * 10u128.pow(24),
+            account_locked_balance: 0,
+            storage_usage: 10u64.pow(6),
+            attached_deposit: 0,
+            prepaid_gas: 10u64.pow(18),
+            random_seed: vec![0, 1, 2],
+            is_view: false,
+            output_data_receivers: vec![],
+            epoch_height: 0,
+        }
+    }
+
+    #[test]
+    fn treasury_accrue_and_withdraw() {
+        testing_env!(get_context(treasury()));
+        let mut contract = Contract::new(None, config());
+
+        contract.accrue_slashed_stake(0, token(), 100, 50);
+        contract.accrue_slashed_stake(1, token(), 20, 10);
+        assert_eq!(contract.get_treasury_balance(token()), U128(120));
+        assert_eq!(contract.get_burned_balance(token()), U128(0));
+
+        contract.resolve_burn(token(), 50, true);
+        contract.resolve_burn(token(), 10, true);
+        assert_eq!(contract.get_burned_balance(token()), U128(60));
+
+        contract.treasury_withdraw(token());
+        contract.resolve_withdraw(token(), 120, true);
+        assert_eq!(contract.get_treasury_balance(token()), U128(0));
+    }
+
+    #[test]
+    fn treasury_withdraw_failed() {
+        testing_env!(get_context(treasury()));
+        let mut contract = Contract::new(None, config());
+
+        contract.accrue_slashed_stake(0, token(), 100, 0);
+        contract.treasury_withdraw(token());
+        assert_eq!(contract.get_treasury_balance(token()), U128(0));
+
+        contract.resolve_withdraw(token(), 100, false);
+        assert_eq!(contract.get_treasury_balance(token()), U128(100));
+    }
+
+    #[test]
+    fn treasury_burn_failed() {
+        testing_env!(get_context(alice()));
+        let mut contract = Contract::new(None, config());
+
+        contract.accrue_slashed_stake(0, token(), 0, 50);
+        contract.resolve_burn(token(), 50, false);
+        assert_eq!(contract.get_burned_balance(token()), U128(0));
+        assert_eq!(contract.get_unburned_balance(token()), U128(50));
+
+        contract.treasury_burn(token());
+        assert_eq!(contract.get_unburned_balance(token()), U128(0));
+
+        contract.resolve_burn(token(), 50, true);
+        assert_eq!(contract.get_burned_balance(token()), U128(50));
+    }
+
+    #[test]
+    #[should_panic(expected = "This method is only callable by the treasury treasury.near")]
+    fn treasury_withdraw_non_treasury() {
+        testing_env!(get_context(alice()));
+        let mut contract = Contract::new(None, config());
+
+        contract.accrue_slashed_stake(0, token(), 100, 50);
+        contract.treasury_withdraw(token());
+    }
+
+    #[test]
+    #[should_panic(expected = "No treasury balance for this token")]
+    fn treasury_withdraw_empty() {
+        testing_env!(get_context(treasury()));
+        let mut contract = Contract::new(None, config());
+
+        contract.treasury_withdraw(token());
+    }
+}
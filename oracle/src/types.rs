@@ -20,10 +20,21 @@ pub struct AnswerNumberType {
     pub negative: bool,
 }
 
+impl AnswerNumberType {
+    // Zero has a single representation so `0` and `-0` are treated as the same answer
+    pub fn canonicalize(self) -> Self {
+        Self {
+            negative: self.negative && self.value.0 != 0,
+            ..self
+        }
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Debug, PartialEq, Clone)]
 pub enum AnswerType {
     Number(AnswerNumberType),
     String(String),
+    Vector(Vec<AnswerNumberType>), // one number per source, in the order of the request's `sources`
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Deserialize, Serialize, Debug, PartialEq, Clone)]
@@ -31,3 +42,21 @@ pub enum Outcome {
     Answer(AnswerType),
     Invalid,
 }
+
+impl Outcome {
+    // @returns the outcome in its canonical form so equal answers compare (and are staked as) equal
+    pub fn canonicalize(self) -> Self {
+        match self {
+            Outcome::Answer(AnswerType::Number(number)) => {
+                Outcome::Answer(AnswerType::Number(number.canonicalize()))
+            }
+            Outcome::Answer(AnswerType::Vector(numbers)) => Outcome::Answer(AnswerType::Vector(
+                numbers
+                    .into_iter()
+                    .map(|number| number.canonicalize())
+                    .collect(),
+            )),
+            outcome => outcome,
+        }
+    }
+}
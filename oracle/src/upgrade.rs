@@ -19,8 +19,9 @@ mod upgrade {
     pub extern "C" fn upgrade() {
         env::setup_panic_hook();
         env::set_blockchain_interface(Box::new(near_blockchain::NearBlockchain {}));
-        let contract: Contract = env::state_read().expect("ERR_CONTRACT_IS_NOT_INITIALIZED");
-        contract.assert_gov();
+        let mut contract: Contract = env::state_read().expect("ERR_CONTRACT_IS_NOT_INITIALIZED");
+        contract.assert_can_upgrade(&env::input().expect("ERR_NO_INPUT"));
+        env::state_write(&contract);
         let current_id = env::current_account_id().into_bytes();
         let method_name = "migrate".as_bytes().to_vec();
 
@@ -59,6 +60,11 @@ mod upgrade {
 }
 
 impl Contract {
+    /**
+     * @notice Reads the state written by the previous code, so upgrades are only supported between versions with the same state layout.
+     * Oracles deployed before governance proposals, roles, subscriptions and the iterable whitelist (which moved from a `LookupMap` to an
+     * `UnorderedMap` under the same prefix) can't be migrated and need a fresh deployment
+     */
     #[init(ignore_state)]
     pub fn migrate() -> Self {
         let contract: Contract = env::state_read().expect("ERR_NOT_INITIALIZED");
@@ -1,15 +1,37 @@
 use crate::*;
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::LookupMap;
+use near_sdk::collections::UnorderedMap;
+use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::AccountId;
 
+const MAX_STAKE_MULTIPLIER: u16 = 50_000; // 5x, denominated in 1e4
+
+// Fields of a `Requester` to change, fields that are `None` are left unchanged
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
+pub struct RequesterPatch {
+    pub contract_name: Option<String>,
+    pub stake_multiplier: Option<u16>,
+    pub code_base_url: Option<String>,
+}
+
+pub(crate) fn assert_valid_stake_multiplier(stake_multiplier: Option<u16>) {
+    if let Some(m) = stake_multiplier {
+        assert!(m > 0, "stake multiplier can't be 0");
+        assert!(
+            m <= MAX_STAKE_MULTIPLIER,
+            "stake multiplier can't exceed {}",
+            MAX_STAKE_MULTIPLIER
+        );
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
-pub struct Whitelist(Option<LookupMap<AccountId, Requester>>); // maps requester account id to requesters config
+pub struct Whitelist(Option<UnorderedMap<AccountId, Requester>>); // maps requester account id to requesters config
 
 impl Whitelist {
     pub fn new(initial_whitelist: Option<Vec<Requester>>) -> Self {
-        let mut whitelist: LookupMap<AccountId, Requester> = LookupMap::new(b"wlr".to_vec());
+        let mut whitelist: UnorderedMap<AccountId, Requester> = UnorderedMap::new(b"wlr".to_vec());
 
         match initial_whitelist {
             Some(initial_whitelist) => {
@@ -54,66 +76,189 @@ impl Whitelist {
             None => Requester::new_no_whitelist(requester),
         }
     }
+
+    // @returns wether requesters need to be whitelisted
+    pub fn is_initiated(&self) -> bool {
+        self.0.is_some()
+    }
+
+    // @returns the previous entry of the requester, if any
+    pub fn insert(&mut self, requester: &Requester) -> Option<Requester> {
+        self.0
+            .as_mut()
+            .expect("Uninitiated whitelist")
+            .insert(&requester.account_id, requester)
+    }
+
+    // @returns up to `limit` requesters starting at `from_index`, in insertion order unless requesters were removed
+    pub fn get_requesters(&self, from_index: u64, limit: u64) -> Vec<Requester> {
+        match &self.0 {
+            Some(whitelist) => {
+                let requesters = whitelist.values_as_vector();
+                (from_index..std::cmp::min(from_index.saturating_add(limit), requesters.len()))
+                    .map(|index| requesters.get(index).unwrap())
+                    .collect()
+            }
+            None => vec![],
+        }
+    }
+
+    // @returns the removed requester, `None` if it wasn't whitelisted
+    pub fn remove(&mut self, requester: &AccountId) -> Option<Requester> {
+        self.0
+            .as_mut()
+            .and_then(|whitelist| whitelist.remove(requester))
+    }
 }
 
 trait WhitelistHandler {
     fn add_to_whitelist(&mut self, new_requester: Requester);
     fn remove_from_whitelist(&mut self, requester: Requester);
+    fn update_requester(&mut self, account_id: AccountId, patch: RequesterPatch);
+    fn report_total_value_secured(&mut self, total_value_secured: U128);
     fn whitelist_contains(&self, requester: AccountId) -> bool;
+    fn get_whitelist(&self, from_index: U64, limit: U64) -> Vec<Requester>;
+    fn get_requester(&self, account_id: AccountId) -> Option<Requester>;
 }
 
 #[near_bindgen]
 impl WhitelistHandler for Contract {
     #[payable]
     fn add_to_whitelist(&mut self, new_requester: Requester) {
-        self.assert_gov();
-
-        match new_requester.stake_multiplier {
-            Some(m) => assert!(m > 0, "stake multiplier can't be 0"),
-            _ => (),
-        };
+        self.assert_role_immediate(Role::WhitelistManager);
 
         let initial_storage = env::storage_usage();
 
-        match &mut self.whitelist.0 {
-            Some(whitelist) => {
-                whitelist.insert(&new_requester.account_id, &new_requester);
-            }
-            None => {
-                let mut whitelist: LookupMap<AccountId, Requester> =
-                    LookupMap::new(b"wlr".to_vec());
-                whitelist.insert(&new_requester.account_id, &new_requester);
-                self.whitelist = Whitelist(Some(whitelist));
-            }
-        };
+        self.whitelist_add(new_requester);
 
-        logger::log_whitelist(&new_requester, true);
         helpers::refund_storage(initial_storage, env::predecessor_account_id());
     }
 
     #[payable]
     fn remove_from_whitelist(&mut self, requester: Requester) {
-        self.assert_gov();
+        self.assert_role_immediate(Role::WhitelistManager);
 
         let initial_storage = env::storage_usage();
 
+        self.whitelist_remove(requester);
+
         helpers::refund_storage(initial_storage, env::predecessor_account_id());
-        logger::log_whitelist(&requester, false);
+    }
 
-        match &mut self.whitelist.0 {
-            Some(whitelist) => {
-                whitelist.remove(&requester.account_id);
-            }
+    /**
+     * @notice Changes the fields of a whitelisted requester that are set in `patch`
+     */
+    #[payable]
+    fn update_requester(&mut self, account_id: AccountId, patch: RequesterPatch) {
+        self.assert_role_immediate(Role::WhitelistManager);
+
+        let initial_storage = env::storage_usage();
+
+        self.whitelist_update(account_id, patch);
+
+        helpers::refund_storage(initial_storage, env::predecessor_account_id());
+    }
+
+    /**
+     * @notice lets a whitelisted requester report the value it secures, which is aggregated into TVS
+     */
+    #[payable]
+    fn report_total_value_secured(&mut self, total_value_secured: U128) {
+        let initial_storage = env::storage_usage();
+        let requester_id = env::predecessor_account_id();
+
+        let mut requester = match &self.whitelist.0 {
+            Some(whitelist) => whitelist
+                .get(&requester_id)
+                .expect("Err predecessor is not whitelisted"),
             None => panic!("Uninitiated whitelist"),
         };
+        let prev_requester_tvs = requester.get_total_value_secured();
+        requester.total_value_secured = Some(total_value_secured);
+
+        if let Some(whitelist) = &mut self.whitelist.0 {
+            whitelist.insert(&requester_id, &requester);
+        }
+        self.update_total_value_secured(prev_requester_tvs, total_value_secured.into());
+
+        logger::log_whitelist(&requester, true);
+        helpers::refund_storage(initial_storage, requester_id);
     }
 
     fn whitelist_contains(&self, requester: AccountId) -> bool {
         self.whitelist.contains(requester)
     }
+
+    fn get_whitelist(&self, from_index: U64, limit: U64) -> Vec<Requester> {
+        self.whitelist
+            .get_requesters(from_index.into(), limit.into())
+    }
+
+    fn get_requester(&self, account_id: AccountId) -> Option<Requester> {
+        match &self.whitelist.0 {
+            Some(whitelist) => whitelist.get(&account_id),
+            None => None,
+        }
+    }
 }
 
 impl Contract {
+    pub fn whitelist_add(&mut self, new_requester: Requester) {
+        assert_valid_stake_multiplier(new_requester.stake_multiplier);
+
+        let prev_requester = match &mut self.whitelist.0 {
+            Some(whitelist) => whitelist.insert(&new_requester.account_id, &new_requester),
+            None => {
+                let mut whitelist: UnorderedMap<AccountId, Requester> =
+                    UnorderedMap::new(b"wlr".to_vec());
+                whitelist.insert(&new_requester.account_id, &new_requester);
+                self.whitelist = Whitelist(Some(whitelist));
+                None
+            }
+        };
+
+        self.update_total_value_secured(
+            prev_requester.map_or(0, |r| r.get_total_value_secured()),
+            new_requester.get_total_value_secured(),
+        );
+
+        logger::log_whitelist(&new_requester, true);
+    }
+
+    pub fn whitelist_remove(&mut self, requester: Requester) {
+        let removed_requester = match &mut self.whitelist.0 {
+            Some(whitelist) => whitelist
+                .remove(&requester.account_id)
+                .expect("requester not whitelisted"),
+            None => panic!("Uninitiated whitelist"),
+        };
+
+        self.update_total_value_secured(removed_requester.get_total_value_secured(), 0);
+
+        logger::log_whitelist(&removed_requester, false);
+    }
+
+    pub fn whitelist_update(&mut self, account_id: AccountId, patch: RequesterPatch) {
+        assert_valid_stake_multiplier(patch.stake_multiplier);
+
+        let prev_requester = self
+            .get_requester(account_id)
+            .expect("requester not whitelisted");
+        let mut requester = prev_requester.clone();
+        if let Some(contract_name) = patch.contract_name {
+            requester.contract_name = contract_name;
+        }
+        if let Some(stake_multiplier) = patch.stake_multiplier {
+            requester.stake_multiplier = Some(stake_multiplier);
+        }
+        if let Some(code_base_url) = patch.code_base_url {
+            requester.code_base_url = Some(code_base_url);
+        }
+        self.whitelist.insert(&requester);
+
+        logger::log_whitelist_update(&prev_requester, &requester);
+    }
+
     pub fn assert_whitelisted(&self, requester: AccountId) {
         match self.whitelist.0 {
             Some(_) => {
@@ -161,25 +306,19 @@ mod mock_token_basic_tests {
             account_id: account.clone(),
             stake_multiplier: None,
             code_base_url: None,
+            total_value_secured: None,
         }
     }
 
     fn config() -> oracle_config::OracleConfig {
         oracle_config::OracleConfig {
-            gov: gov(),
-            final_arbitrator: alice(),
-            payment_token: token(),
-            stake_token: token(),
             validity_bond: U128(0),
-            max_outcomes: 8,
-            default_challenge_window_duration: U64(1000),
-            min_initial_challenge_window_duration: U64(1000),
             final_arbitrator_invoke_amount: U128(25_000_000_000_000_000_000_000_000_000_000),
             fee: FeeConfig {
-                flux_market_cap: U128(50000),
-                total_value_staked: U128(10000),
                 resolution_fee_percentage: 5000, // 5%
+                ..test_utils::config().fee
             },
+            ..test_utils::config()
         }
     }
 
@@ -230,6 +369,15 @@ mod mock_token_basic_tests {
         assert!(!contract.whitelist_contains(alice()));
     }
 
+    #[test]
+    #[should_panic(expected = "requester not whitelisted")]
+    fn whitelist_remove_unknown() {
+        testing_env!(get_context(gov()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        contract.remove_from_whitelist(registry_entry(alice()));
+    }
+
     #[test]
     fn whitelist_assert_passes_when_empty() {
         testing_env!(get_context(gov()));
@@ -247,6 +395,172 @@ mod mock_token_basic_tests {
         contract.add_to_whitelist(registry_entry(alice()));
     }
 
+    #[test]
+    fn whitelist_report_total_value_secured() {
+        testing_env!(get_context(gov()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut config = config();
+        config.fee.total_value_staked = U128(400);
+        let mut contract = Contract::new(whitelist, config);
+        // no reports yet, the TVS from the initial config is used
+        assert_eq!(contract.get_total_value_staked(), U128(400));
+
+        testing_env!(get_context(bob()));
+        contract.report_total_value_secured(U128(300));
+        testing_env!(get_context(carol()));
+        contract.report_total_value_secured(U128(200));
+        assert_eq!(contract.get_total_value_staked(), U128(500));
+
+        // governance's TVS is a floor for the reported TVS
+        testing_env!(get_context(bob()));
+        contract.report_total_value_secured(U128(100));
+        assert_eq!(contract.total_value_secured, 300);
+        assert_eq!(contract.get_total_value_staked(), U128(400));
+
+        testing_env!(get_context(gov()));
+        contract.remove_from_whitelist(registry_entry(carol()));
+        assert_eq!(contract.total_value_secured, 100);
+        assert_eq!(contract.get_config().fee.total_value_staked, U128(400));
+    }
+
+    #[test]
+    #[should_panic(expected = "TVS must be lower than market cap")]
+    fn whitelist_report_total_value_secured_above_market_cap() {
+        testing_env!(get_context(bob()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        contract.report_total_value_secured(U128(50000));
+    }
+
+    #[test]
+    fn whitelist_initial_total_value_secured() {
+        testing_env!(get_context(gov()));
+        let mut bob_requester = registry_entry(bob());
+        bob_requester.total_value_secured = Some(U128(30000));
+        let whitelist = Some(vec![bob_requester, registry_entry(carol())]);
+        let contract = Contract::new(whitelist, config());
+        assert_eq!(contract.get_total_value_staked(), U128(30000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Err predecessor is not whitelisted")]
+    fn whitelist_report_non_whitelisted() {
+        testing_env!(get_context(alice()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        contract.report_total_value_secured(U128(300));
+    }
+
+    #[test]
+    fn whitelist_enumeration() {
+        testing_env!(get_context(gov()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+        contract.add_to_whitelist(registry_entry(alice()));
+
+        let requesters: Vec<AccountId> = contract
+            .get_whitelist(U64(0), U64(10))
+            .into_iter()
+            .map(|requester| requester.account_id)
+            .collect();
+        assert_eq!(requesters, vec![bob(), carol(), alice()]);
+
+        let requesters = contract.get_whitelist(U64(1), U64(1));
+        assert_eq!(requesters.len(), 1);
+        assert_eq!(requesters[0].account_id, carol());
+
+        contract.remove_from_whitelist(registry_entry(bob()));
+        assert_eq!(contract.get_whitelist(U64(0), U64(10)).len(), 2);
+        assert!(contract.get_requester(bob()).is_none());
+        assert_eq!(
+            contract.get_requester(alice()).unwrap().contract_name,
+            alice()
+        );
+    }
+
+    #[test]
+    fn whitelist_update_requester() {
+        testing_env!(get_context(gov()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+
+        contract.update_requester(
+            bob(),
+            RequesterPatch {
+                contract_name: None,
+                stake_multiplier: Some(20000),
+                code_base_url: Some("https://github.com/bob".to_string()),
+            },
+        );
+
+        let requester = contract.get_requester(bob()).unwrap();
+        assert_eq!(requester.contract_name, bob());
+        assert_eq!(requester.stake_multiplier, Some(20000));
+        assert_eq!(
+            requester.code_base_url,
+            Some("https://github.com/bob".to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "stake multiplier can't exceed 50000")]
+    fn whitelist_update_requester_multiplier_exceed() {
+        testing_env!(get_context(gov()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+
+        contract.update_requester(
+            bob(),
+            RequesterPatch {
+                contract_name: None,
+                stake_multiplier: Some(50001),
+                code_base_url: None,
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "requester not whitelisted")]
+    fn whitelist_update_non_whitelisted() {
+        testing_env!(get_context(gov()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+
+        contract.update_requester(
+            alice(),
+            RequesterPatch {
+                contract_name: Some("alice".to_string()),
+                stake_multiplier: None,
+                code_base_url: None,
+            },
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "This method is only callable by the governance contract gov.near")]
+    fn only_gov_can_update() {
+        testing_env!(get_context(alice()));
+        let whitelist = Some(vec![registry_entry(bob()), registry_entry(carol())]);
+        let mut contract = Contract::new(whitelist, config());
+
+        contract.update_requester(
+            bob(),
+            RequesterPatch {
+                contract_name: None,
+                stake_multiplier: Some(20000),
+                code_base_url: None,
+            },
+        );
+    }
+
+    #[test]
+    fn whitelist_enumeration_without_whitelist() {
+        testing_env!(get_context(gov()));
+        let contract = Contract::new(None, config());
+        assert!(contract.get_whitelist(U64(0), U64(10)).is_empty());
+        assert!(contract.get_requester(bob()).is_none());
+    }
+
     #[test]
     #[should_panic(expected = "This method is only callable by the governance contract gov.near")]
     fn only_gov_can_remove() {
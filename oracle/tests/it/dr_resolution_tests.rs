@@ -1,5 +1,5 @@
 use crate::utils::*;
-use oracle::data_request::PERCENTAGE_DIVISOR;
+use oracle::data_request::{DeliveryStatus, PERCENTAGE_DIVISOR};
 
 #[test]
 fn dr_claim_flow() {
@@ -11,6 +11,8 @@ fn dr_claim_flow() {
     let init_res = TestUtils::init(None);
     let init_balance_alice = init_res.alice.get_token_balance(None);
 
+    // 5% of the value secured by the requester
+    init_res.alice.set_requester_tvs(100, None);
     let _res = init_res.alice.dr_new(fee, None);
     let post_new_balance_oracle = init_res.alice.get_token_balance(Some(ORACLE_CONTRACT_ID.to_string()));
     assert_eq!(post_new_balance_oracle, validity_bond + fee);
@@ -49,6 +51,8 @@ fn dr_fixed_fee_flow() {
 
     let init_balance_alice = init_res.alice.get_token_balance(None);
 
+    // 5% of the value secured by the requester
+    init_res.alice.set_requester_tvs(2000, None);
     let _res = init_res.alice.dr_new(custom_fee_amount, Some(1));
     let _post_new_balance_oracle = init_res.alice.get_token_balance(Some(ORACLE_CONTRACT_ID.to_string()));
     
@@ -85,8 +89,9 @@ fn dr_multiplier_flow() {
         validity_bond: 1,
         final_arbitrator_invoke_amount: 2500
     }));
-        let init_balance_alice = init_res.alice.get_token_balance(None);
+    let init_balance_alice = init_res.alice.get_token_balance(None);
 
+    init_res.alice.set_requester_tvs(2000, Some(multiplier_amount));
     let _res = init_res.alice.dr_new(100, Some(1));
     let _post_new_balance_oracle = init_res.alice.get_token_balance(Some(ORACLE_CONTRACT_ID.to_string()));
     
@@ -110,3 +115,56 @@ fn dr_multiplier_flow() {
     let post_claim_balance_alice = init_res.alice.get_token_balance(None);
     assert_eq!(post_claim_balance_alice, init_balance_alice);
 }
+
+#[test]
+fn dr_finalize_delivery_flow() {
+    let init_res = TestUtils::init(None);
+    let _res = init_res.alice.dr_new(0, None);
+    let outcome = Outcome::Answer(
+        AnswerType::String("test".to_string())
+    );
+    let _res = init_res.alice.stake(0, outcome, calc_bond_size(VALIDITY_BOND, 0, None));
+
+    init_res.alice.finalize(0);
+
+    let request = init_res.alice.get_request(0).unwrap();
+    assert_eq!(request.delivery_status, Some(DeliveryStatus::Delivered));
+}
+
+#[test]
+fn dr_final_arbitrator_contract_flow() {
+    let init_res = TestUtils::init(Some(TestSetupArgs {
+        stake_multiplier: None,
+        validity_bond: VALIDITY_BOND,
+        final_arbitrator_invoke_amount: calc_bond_size(VALIDITY_BOND, 1, None)
+    }));
+    let mut config = init_res.alice.get_config();
+    config.final_arbitrator = ARBITRATOR_CONTRACT_ID.to_string();
+    config.invoke_final_arbitrator_contract = true;
+    init_res.alice.set_config(config);
+
+    let _res = init_res.alice.dr_new(0, None);
+    let correct_outcome = Outcome::Answer(
+        AnswerType::String("test".to_string())
+    );
+    let incorrect_outcome = Outcome::Answer(
+        AnswerType::String("test_wrong".to_string())
+    );
+    let _res = init_res.bob.stake(0, correct_outcome.clone(), calc_bond_size(VALIDITY_BOND, 0, None));
+    // bonding the second round invokes the arbitrator contract, which sides with the first round right away
+    let _res = init_res.carol.stake(0, incorrect_outcome, calc_bond_size(VALIDITY_BOND, 1, None));
+
+    let request = init_res.alice.get_request(0).unwrap();
+    assert!(request.final_arbitrator_triggered);
+    assert_eq!(request.finalized_outcome, Some(correct_outcome));
+    assert_eq!(request.delivery_status, Some(DeliveryStatus::Delivered));
+
+    // bob's stake is returned along with carol's incorrect stake
+    let pre_claim_balance_bob = init_res.bob.get_token_balance(None);
+    init_res.bob.claim(0);
+    let post_claim_balance_bob = init_res.bob.get_token_balance(None);
+    assert_eq!(
+        post_claim_balance_bob,
+        pre_claim_balance_bob + calc_bond_size(VALIDITY_BOND, 0, None) + calc_bond_size(VALIDITY_BOND, 1, None)
+    );
+}
@@ -0,0 +1,67 @@
+use crate::utils::*;
+
+#[test]
+fn dr_unstake_flow() {
+    let init_res = TestUtils::init(None);
+    let init_balance_bob = init_res.bob.get_token_balance(None);
+    let init_balance_carol = init_res.carol.get_token_balance(None);
+
+    let _res = init_res.alice.dr_new(0, None);
+    let bond_size = calc_bond_size(VALIDITY_BOND, 0, None);
+    let correct_outcome = Outcome::Answer(AnswerType::String("test".to_string()));
+    let incorrect_outcome = Outcome::Answer(AnswerType::String("test_wrong".to_string()));
+
+    // carol partially unstakes from round 0 before bob bonds it
+    let _res = init_res.carol.stake(0, correct_outcome.clone(), bond_size / 2);
+    let _res = init_res.carol.unstake(0, 0, correct_outcome.clone(), bond_size / 4);
+    let _res = init_res.bob.stake(0, correct_outcome, bond_size - bond_size / 4);
+
+    // carol partially disputes in round 1 and withdraws again
+    let _res = init_res.carol.stake(0, incorrect_outcome.clone(), bond_size);
+    let post_stake_balance_carol = init_res.carol.get_token_balance(None);
+    assert_eq!(post_stake_balance_carol, init_balance_carol - bond_size / 4 - bond_size);
+
+    let _res = init_res.carol.unstake(0, 1, incorrect_outcome, bond_size);
+    let post_unstake_balance_carol = init_res.carol.get_token_balance(None);
+    assert_eq!(post_unstake_balance_carol, init_balance_carol - bond_size / 4);
+
+    init_res.alice.finalize(0);
+    init_res.bob.claim(0);
+    init_res.carol.claim(0);
+
+    let post_claim_balance_bob = init_res.bob.get_token_balance(None);
+    assert_eq!(post_claim_balance_bob, init_balance_bob);
+    // carol's claim only pays out the stake she left in round 0
+    let post_claim_balance_carol = init_res.carol.get_token_balance(None);
+    assert_eq!(post_claim_balance_carol, post_unstake_balance_carol + bond_size / 4);
+}
+
+#[test]
+#[should_panic(expected = "Cannot withdraw from bonded outcome")]
+fn dr_unstake_bonded_outcome() {
+    let init_res = TestUtils::init(None);
+
+    let _res = init_res.alice.dr_new(0, None);
+    let bond_size = calc_bond_size(VALIDITY_BOND, 0, None);
+    let outcome = Outcome::Answer(AnswerType::String("test".to_string()));
+
+    let _res = init_res.bob.stake(0, outcome.clone(), bond_size);
+    let _res = init_res.bob.unstake(0, 0, outcome, bond_size);
+}
+
+#[test]
+fn dr_unstake_twice_flow() {
+    let init_res = TestUtils::init(None);
+    let init_balance_carol = init_res.carol.get_token_balance(None);
+
+    let _res = init_res.alice.dr_new(0, None);
+    let bond_size = calc_bond_size(VALIDITY_BOND, 0, None);
+    let outcome = Outcome::Answer(AnswerType::String("test".to_string()));
+
+    let _res = init_res.carol.stake(0, outcome.clone(), bond_size / 2);
+    let _res = init_res.carol.unstake(0, 0, outcome.clone(), bond_size / 4);
+    let _res = init_res.carol.unstake(0, 0, outcome, bond_size / 4);
+
+    let post_unstake_balance_carol = init_res.carol.get_token_balance(None);
+    assert_eq!(post_unstake_balance_carol, init_balance_carol);
+}
@@ -4,4 +4,5 @@ mod utils;
 mod init;
 mod dr_resolution_tests;
 mod dr_basic_tests;
-mod dr_scenario_tests;
\ No newline at end of file
+mod dr_scenario_tests;
+mod dr_unstake_tests;
\ No newline at end of file
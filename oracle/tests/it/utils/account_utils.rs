@@ -1,5 +1,6 @@
 use crate::utils::*;
-use oracle::data_request::DataRequestDataType;
+use oracle::data_request::{DataRequestDataType, DataRequestSummary};
+use oracle::oracle_config::OracleConfig;
 use oracle::types::*;
 pub fn init_balance() -> u128 {
     to_yocto("100000")
@@ -57,6 +58,24 @@ impl TestAccount {
         ).unwrap_json()
     }
 
+    pub fn get_request(&self, id: u64) -> Option<DataRequestSummary> {
+        self.account.view(
+            ORACLE_CONTRACT_ID.to_string(),
+            "get_request_by_id",
+            json!({
+                "id": U64(id)
+            }).to_string().as_bytes()
+        ).unwrap_json()
+    }
+
+    pub fn get_config(&self) -> OracleConfig {
+        self.account.view(
+            ORACLE_CONTRACT_ID.to_string(),
+            "get_config",
+            json!({}).to_string().as_bytes()
+        ).unwrap_json()
+    }
+
     pub fn get_outcome(&self, id: u64) -> Option<Outcome> {
         self.account.call(
             ORACLE_CONTRACT_ID.to_string(),
@@ -101,6 +120,8 @@ impl TestAccount {
                     outcomes: None,
                     challenge_period: U64(1000),
                     data_type: DataRequestDataType::String,
+                    reveal_period: None,
+                    settlement_time: None,
                     creator: self.account.account_id(),
                 }
             }).to_string().as_bytes(),
@@ -112,6 +133,50 @@ impl TestAccount {
         dr_new_res
     }
 
+    // Re-registers the requester contract with the value it secures, which determines its resolution fee
+    pub fn set_requester_tvs(
+        &self,
+        total_value_secured: u128,
+        stake_multiplier: Option<u16>
+    ) -> ExecutionResult {
+        let res = self.account.call(
+            ORACLE_CONTRACT_ID.to_string(), 
+            "add_to_whitelist", 
+            json!({
+                "new_requester": Requester {
+                    code_base_url: None,
+                    account_id: REQUESTER_CONTRACT_ID.to_string(),
+                    contract_name: "test".to_string(),
+                    stake_multiplier,
+                    total_value_secured: Some(U128(total_value_secured))
+                }
+            }).to_string().as_bytes(),
+            DEFAULT_GAS,
+            to_yocto("1")
+        );
+
+        res.assert_success();
+        res
+    }
+
+    pub fn set_config(
+        &self,
+        new_config: OracleConfig
+    ) -> ExecutionResult {
+        let res = self.account.call(
+            ORACLE_CONTRACT_ID.to_string(), 
+            "set_config", 
+            json!({
+                "new_config": new_config
+            }).to_string().as_bytes(),
+            DEFAULT_GAS,
+            to_yocto("1")
+        );
+
+        res.assert_success();
+        res
+    }
+
     pub fn stake(
         &self,
         dr_id: u64, 
@@ -129,6 +194,30 @@ impl TestAccount {
         res
     }
 
+    pub fn unstake(
+        &self,
+        dr_id: u64,
+        round: u16,
+        outcome: Outcome,
+        amount: u128
+    ) -> ExecutionResult {
+        let res = self.account.call(
+            ORACLE_CONTRACT_ID.to_string(), 
+            "dr_unstake", 
+            json!({
+                "request_id": U64(dr_id),
+                "resolution_round": round,
+                "outcome": outcome,
+                "amount": U128(amount)
+            }).to_string().as_bytes(),
+            DEFAULT_GAS,
+            1000000000000000000000
+        );
+
+        res.assert_success();
+        res
+    }
+
     pub fn finalize(
         &self,
         dr_id: u64
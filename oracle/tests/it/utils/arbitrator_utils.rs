@@ -0,0 +1,27 @@
+use crate::utils::*;
+pub struct ArbitratorUtils {
+    pub contract: ContractAccount<ArbitratorContract>
+}
+
+impl ArbitratorUtils {
+    pub fn new(master_account: &TestAccount) -> Self {
+        // deploy mock final arbitrator
+        let contract = deploy!(
+            // Contract Proxy
+            contract: ArbitratorContract,
+            // Contract account id
+            contract_id: ARBITRATOR_CONTRACT_ID,
+            // Bytes of contract
+            bytes: &ARBITRATOR_WASM_BYTES,
+            // User deploying the contract,
+            signer_account: master_account.account,
+            deposit: to_yocto("1000"),
+            // init method
+            init_method: new()
+        );
+
+        Self {
+            contract
+        }
+    }
+}
@@ -23,6 +23,7 @@ mod account_utils;
 mod oracle_utils;
 mod token_utils;
 mod requester_contract_utils;
+mod arbitrator_utils;
 mod deposit;
 
 // pub use account_utils::*;
@@ -33,6 +34,7 @@ pub use account_utils::*;
 use deposit::*;
 use request_interface;
 use token;
+use mock_arbitrator;
 use oracle::data_request::PERCENTAGE_DIVISOR;
 use uint::construct_uint;
 
@@ -44,10 +46,12 @@ construct_uint! {
 type OracleContract = oracle::ContractContract;
 type RequesterContract = request_interface::RequesterContractContract;
 type TokenContract = token::TokenContractContract;
+type ArbitratorContract = mock_arbitrator::MockArbitratorContract;
 
 pub const TOKEN_CONTRACT_ID: &str = "token";
 pub const ORACLE_CONTRACT_ID: &str = "oracle";
 pub const REQUESTER_CONTRACT_ID: &str = "requester";
+pub const ARBITRATOR_CONTRACT_ID: &str = "arbitrator";
 pub const SAFE_STORAGE_AMOUNT: u128 = 1250000000000000000000;
 pub const VALIDITY_BOND: u128 = 100;
 
@@ -67,7 +71,8 @@ pub fn calc_bond_size(validity_bond: u128, round: u32, multiplier: Option<u16>)
 near_sdk_sim::lazy_static_include::lazy_static_include_bytes! {
     ORACLE_WASM_BYTES => "../res/oracle.wasm",
     REQUESTER_CONTRACT_WASM_BYTES => "../res/request_interface.wasm",
-    TOKEN_WASM_BYTES => "../res/token.wasm"
+    TOKEN_WASM_BYTES => "../res/token.wasm",
+    ARBITRATOR_WASM_BYTES => "../res/mock_arbitrator.wasm"
 }
 
 pub struct TestUtils {
@@ -75,6 +80,7 @@ pub struct TestUtils {
     pub oracle_contract: ContractAccount<OracleContract>,
     pub token_contract: ContractAccount<TokenContract>,
     pub requester_contract: ContractAccount<RequesterContract>,
+    pub arbitrator_contract: ContractAccount<ArbitratorContract>,
     pub alice: account_utils::TestAccount,
     pub bob: account_utils::TestAccount,
     pub carol: account_utils::TestAccount,
@@ -107,6 +113,7 @@ impl TestUtils {
         let token_init_res = token_utils::TokenUtils::new(&master_account); // Init token
         let oracle_init_res = oracle_utils::OracleUtils::new(&master_account, args.validity_bond, args.final_arbitrator_invoke_amount, args.stake_multiplier);  // Init oracle
         let requester_contract_init_res = requester_contract_utils::RequesterContractUtils::new(&master_account);
+        let arbitrator_init_res = arbitrator_utils::ArbitratorUtils::new(&master_account);
 
         Self {
             alice: TestAccount::new(Some(&master_account.account), Some("alice")),
@@ -119,6 +126,7 @@ impl TestUtils {
             treasurer: TestAccount::new(Some(&master_account.account), Some("treasurer")),
             master_account: master_account,
             requester_contract: requester_contract_init_res.contract,
+            arbitrator_contract: arbitrator_init_res.contract,
             oracle_contract: oracle_init_res.contract,
             token_contract: token_init_res.contract
         }
@@ -1,5 +1,5 @@
 use crate::utils::*;
-use oracle::oracle_config::OracleConfig;
+use oracle::oracle_config::{FinalArbitratorFallback, OracleConfig, UnbondedOutcomePolicy};
 use oracle::{Requester};
 use oracle::fee_config::FeeConfig;
 
@@ -12,7 +12,8 @@ fn new_registry_entry(contract_id: String, stake_multiplier: Option<u16>) -> Req
         code_base_url: None,
         account_id: contract_id,
         contract_name: "test".to_string(),
-        stake_multiplier
+        stake_multiplier,
+        total_value_secured: None
     }
 }
 
@@ -28,15 +29,32 @@ impl OracleUtils {
             final_arbitrator: "alice".to_string(),
             payment_token: TOKEN_CONTRACT_ID.to_string(),
             stake_token: TOKEN_CONTRACT_ID.to_string(),
+            payment_tokens: vec![],
             validity_bond: U128(validity_bond),
             max_outcomes: 8,
             default_challenge_window_duration: U64(1000),
             min_initial_challenge_window_duration: U64(1000),
+            request_expiry_duration: U64(5000),
+            max_settlement_duration: U64(10000),
+            forfeit_unrevealed_stakes: false,
+            treasury: "alice".to_string(),
+            treasury_share: 0,
+            burn_share: 0,
+            burn_account: "alice".to_string(),
+            invoke_final_arbitrator_contract: false,
+            final_arbitrator_deadline: U64(5000),
+            final_arbitrator_fallback: FinalArbitratorFallback::LastBondedOutcome,
+            unbonded_outcome_policy: UnbondedOutcomePolicy::Refund,
+            registration_bond: None,
+            registration_cooldown: U64(5000),
+            registration_stake_multiplier: None,
+            governance_delay: U64(0),
             final_arbitrator_invoke_amount: U128(final_arbitrator_invoke_amount),
             fee: FeeConfig {
                 flux_market_cap: U128(50000),
                 total_value_staked: U128(10000),
                 resolution_fee_percentage: 5000, // 5%
+                min_resolution_fee: U128(0),
             }
         };
         